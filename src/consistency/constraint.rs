@@ -32,6 +32,11 @@ impl<const N: usize> Constraint<N> {
       no_tiles,
     }
   }
+
+  /// The number of tiles the constraint is defined over
+  pub fn no_tiles(&self) -> usize {
+    self.no_tiles
+  }
 }
 
 impl<const N: usize> Index<(usize, usize, usize)> for Constraint<N> {
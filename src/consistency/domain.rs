@@ -1,5 +1,7 @@
 use super::Constraint;
-use std::array;
+#[cfg(feature = "serde")]
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{array, sync::Arc};
 
 /// A generic domain for a given cell, defined over the valid indices
 ///
@@ -10,8 +12,97 @@ use std::array;
 pub struct Domain<const N: usize> {
   num_valid: usize,
   entries: Vec<(bool, [usize; N])>,
+  /// Weights given by a cell selector, i.e. to rank the domain by entropy
+  weights: Option<WeightSums>,
+  /// Weights given by a sampler, to sample items without summing weights
+  sampling: Option<WeightTree>,
 }
 
+/// The weight of an item, where items without a weight (or with a negative
+/// weight) are weighted by `0`
+fn weight(weights: &[f64], item: usize) -> f64 {
+  weights.get(item).copied().unwrap_or(0.0).max(0.0)
+}
+
+/// Running sums over the weights of the items left in a domain, kept up to
+/// date as items are removed so that its entropy can be found without iterating
+/// over every item.
+#[derive(Clone, Debug)]
+struct WeightSums {
+  weights: Arc<[f64]>,
+  /// The sum of `w` over every item left
+  sum: f64,
+  /// The sum of `w log w` over every item left
+  sum_log: f64,
+}
+
+impl WeightSums {
+  fn remove(&mut self, item: usize) {
+    let w = weight(&self.weights, item);
+    if w > 0.0 {
+      self.sum -= w;
+      self.sum_log -= w * w.ln();
+    }
+  }
+}
+
+impl PartialEq for WeightSums {
+  fn eq(&self, other: &Self) -> bool {
+    self.weights == other.weights
+      && self.sum.total_cmp(&other.sum).is_eq()
+      && self.sum_log.total_cmp(&other.sum_log).is_eq()
+  }
+}
+impl Eq for WeightSums {}
+
+/// A Fenwick tree over the weights of the items left in a domain, kept up to
+/// date as items are removed so that items can be sampled without iterating
+/// over every item.
+#[derive(Clone, Debug)]
+struct WeightTree {
+  weights: Arc<[f64]>,
+  /// The sum of `w` over every item left
+  sum: f64,
+  /// Where `tree[i]` is the sum of the `i & -i` weights up to item `i - 1`
+  tree: Vec<f64>,
+}
+
+impl WeightTree {
+  fn remove(&mut self, item: usize) {
+    let w = weight(&self.weights, item);
+    if w > 0.0 {
+      self.sum -= w;
+
+      let mut i = item + 1;
+      while i < self.tree.len() {
+        self.tree[i] -= w;
+        i += i & i.wrapping_neg();
+      }
+    }
+  }
+
+  /// Finds the first item whose cumulative weight is past the point
+  fn find(&self, point: f64) -> usize {
+    let (mut i, mut left) = (0, point);
+    let mut step = self.tree.len().next_power_of_two();
+    while step > 0 {
+      if i + step < self.tree.len() && self.tree[i + step] <= left {
+        i += step;
+        left -= self.tree[i];
+      }
+      step /= 2;
+    }
+    i
+  }
+}
+
+impl PartialEq for WeightTree {
+  fn eq(&self, other: &Self) -> bool {
+    self.weights == other.weights && self.sum.total_cmp(&other.sum).is_eq()
+  }
+}
+impl Eq for WeightTree {}
+
 impl<const N: usize> Domain<N> {
   /// Generates a completely empty domain
   pub fn empty(size: usize) -> Self {
@@ -19,6 +110,8 @@ impl<const N: usize> Domain<N> {
     Self {
       num_valid: 0,
       entries: (0..size).map(|_| empty_entry).collect(),
+      weights: None,
+      sampling: None,
     }
   }
 
@@ -47,7 +140,86 @@ impl<const N: usize> Domain<N> {
     Domain {
       num_valid: entries.iter().filter(|entry| entry.0).count(),
       entries,
+      weights: None,
+      sampling: None,
+    }
+  }
+
+  /// Weights each item in the domain, so that the domain's entropy is kept
+  /// track of as items are removed.<br>
+  /// Items without a weight (or with a negative weight) are weighted by `0`.
+  pub fn set_weights(&mut self, weights: Arc<[f64]>) {
+    let mut sums = WeightSums {
+      weights,
+      sum: 0.0,
+      sum_log: 0.0,
+    };
+    for item in self.iter() {
+      let w = weight(&sums.weights, item);
+      if w > 0.0 {
+        sums.sum += w;
+        sums.sum_log += w * w.ln();
+      }
+    }
+    self.weights = Some(sums);
+  }
+
+  /// The weights given to the items in the domain to find its entropy, if it's
+  /// been weighted
+  pub fn weights(&self) -> Option<&Arc<[f64]>> {
+    self.weights.as_ref().map(|sums| &sums.weights)
+  }
+
+  /// Weights each item in the domain for sampling, so that items can be found
+  /// by their cumulative weight as items are removed.<br>
+  /// These are kept apart from the weights used for entropy, so that a sampler
+  /// and a selector can weight the same domain differently.<br>
+  /// Items without a weight (or with a negative weight) are weighted by `0`.
+  pub fn set_sampling_weights(&mut self, weights: Arc<[f64]>) {
+    let mut tree = WeightTree {
+      weights,
+      sum: 0.0,
+      tree: vec![0.0; self.entries.len() + 1],
+    };
+    for item in self.iter() {
+      let w = weight(&tree.weights, item);
+      if w > 0.0 {
+        tree.sum += w;
+        tree.tree[item + 1] = w;
+      }
     }
+
+    // @note each node is added to its parent once, building the tree in O(n)
+    for i in 1..tree.tree.len() {
+      let parent = i + (i & i.wrapping_neg());
+      if parent < tree.tree.len() {
+        tree.tree[parent] += tree.tree[i];
+      }
+    }
+    self.sampling = Some(tree);
+  }
+
+  /// The weights given to the items in the domain for sampling, if it's been
+  /// weighted for sampling
+  pub fn sampling_weights(&self) -> Option<&Arc<[f64]>> {
+    self.sampling.as_ref().map(|tree| &tree.weights)
+  }
+
+  /// The total weight of the items left in the domain, if it's been weighted
+  /// for sampling
+  pub fn total_weight(&self) -> Option<f64> {
+    self.sampling.as_ref().map(|tree| tree.sum.max(0.0))
+  }
+
+  /// Finds the item at a point along the cumulative weights of the items left,
+  /// where the point is between `0` and the total weight, in `O(log n)`.<br>
+  /// Returns `None` if the domain hasn't been weighted for sampling, or the
+  /// point is past the last item.
+  pub fn item_at_weight(&self, point: f64) -> Option<usize> {
+    let item = self.sampling.as_ref()?.find(point);
+    // @note the tree can drift slightly as items are removed, so the point may
+    // land on an item that's already been removed
+    self.contains(item).then_some(item)
   }
 }
 
@@ -66,9 +238,26 @@ impl<const N: usize> Domain<N> {
     self.num_valid == 1
   }
 
+  /// The only valid item left in the domain, if there is exactly one
+  pub fn single_item(&self) -> Option<usize> {
+    self.is_single().then(|| self.iter().next()).flatten()
+  }
+
+  /// The Shannon entropy of the weighted items left in the domain, given by
+  /// `H = log(Σw) - Σ(w log w) / Σw`.<br>
+  /// Returns `None` if the domain hasn't been weighted.
+  pub fn entropy(&self) -> Option<f64> {
+    let sums = self.weights.as_ref()?;
+    // @note the sums can drift slightly below zero as items are removed
+    Some(match sums.sum > f64::EPSILON {
+      true => (sums.sum.ln() - sums.sum_log / sums.sum).max(0.0),
+      false => 0.0,
+    })
+  }
+
   /// Whether the domain contains the given item
   pub fn contains(&self, item: usize) -> bool {
-    self.entries.get(item).map_or(false, |entry| entry.0)
+    self.entries.get(item).is_some_and(|entry| entry.0)
   }
 
   /// Removes an item from this domain
@@ -78,6 +267,12 @@ impl<const N: usize> Domain<N> {
     }
     self.entries[item].0 = false;
     self.num_valid -= 1;
+    if let Some(sums) = self.weights.as_mut() {
+      sums.remove(item);
+    }
+    if let Some(tree) = self.sampling.as_mut() {
+      tree.remove(item);
+    }
     true
   }
 
@@ -96,9 +291,36 @@ impl<const N: usize> Domain<N> {
 
     entry.0 = false;
     self.num_valid -= 1;
+    if let Some(sums) = self.weights.as_mut() {
+      sums.remove(item);
+    }
+    if let Some(tree) = self.sampling.as_mut() {
+      tree.remove(item);
+    }
     true
   }
 
+  /// Recalculates how many items in the neighbouring domain on a given side
+  /// support each item in this domain.
+  pub fn recount_side(&mut self, side: usize, constraint: &Constraint<N>, neighbour: &Domain<N>) {
+    for (item, entry) in self.entries.iter_mut().enumerate() {
+      entry.1[side] = (neighbour.iter())
+        .filter(|&tile| constraint[(item, tile, side)])
+        .count();
+    }
+  }
+
+  /// Removes all items without any support on a given side, returning them
+  pub fn remove_unsupported(&mut self, side: usize) -> Vec<usize> {
+    let unsupported: Vec<_> = (self.iter())
+      .filter(|&item| self.entries[item].1[side] == 0)
+      .collect();
+    unsupported.iter().for_each(|&item| {
+      self.remove_item(item);
+    });
+    unsupported
+  }
+
   pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
     self
       .entries
@@ -120,6 +342,43 @@ impl<const N: usize> IntoIterator for Domain<N> {
   }
 }
 
+#[cfg(feature = "serde")]
+impl<const N: usize> Serialize for Domain<N> {
+  fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+    // serde can't handle arrays of a generic length, so we use slices instead
+    // @note weights aren't saved, as they're shared between many domains
+    serializer.collect_seq(
+      self
+        .entries
+        .iter()
+        .map(|(valid, counts)| (valid, counts.as_slice())),
+    )
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> Deserialize<'de> for Domain<N> {
+  fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    let entries = Vec::<(bool, Vec<usize>)>::deserialize(deserializer)?
+      .into_iter()
+      .map(|(valid, counts)| {
+        let len = counts.len();
+        let counts = counts
+          .try_into()
+          .map_err(|_| De::Error::invalid_length(len, &"a count for each side"))?;
+        Ok((valid, counts))
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Self {
+      num_valid: entries.iter().filter(|entry| entry.0).count(),
+      entries,
+      weights: None,
+      sampling: None,
+    })
+  }
+}
+
 #[derive(Clone)]
 pub struct DomainIter<const N: usize> {
   i: usize,
@@ -8,6 +8,7 @@ pub struct AC3Error<Idx> {
   domain: Idx,
   item: usize,
   kind: AC3ErrorKind,
+  wipeout: Option<(Idx, usize)>,
 }
 
 impl<Idx: Display> Display for AC3Error<Idx> {
@@ -38,8 +39,20 @@ impl<Idx> AC3Error<Idx> {
       domain,
       item,
       kind: kind.into(),
+      wipeout: None,
     }
   }
+
+  /// Records where propagation wiped out a domain to cause this error
+  pub fn with_wipeout(self, wipeout: Option<(Idx, usize)>) -> Self {
+    Self { wipeout, ..self }
+  }
+
+  /// The cell whose domain was wiped out, alongside the side it lost its last
+  /// supports from, if that's what caused this error
+  pub fn wipeout(&self) -> Option<&(Idx, usize)> {
+    self.wipeout.as_ref()
+  }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -58,3 +71,36 @@ impl From<WorkerBagError<AC3ErrorKind>> for AC3ErrorKind {
     }
   }
 }
+
+/// An error produced whilst propagating the removal of tiles
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PropagationError<Idx> {
+  pub kind: AC3ErrorKind,
+  /// The cell whose domain was wiped out, alongside the side it lost its last
+  /// supports from
+  pub wipeout: Option<(Idx, usize)>,
+}
+
+impl<Idx> From<AC3ErrorKind> for PropagationError<Idx> {
+  fn from(kind: AC3ErrorKind) -> Self {
+    Self {
+      kind,
+      wipeout: None,
+    }
+  }
+}
+
+impl<Idx> From<PropagationError<Idx>> for AC3ErrorKind {
+  fn from(value: PropagationError<Idx>) -> Self {
+    value.kind
+  }
+}
+
+impl<Idx> From<WorkerBagError<PropagationError<Idx>>> for PropagationError<Idx> {
+  fn from(value: WorkerBagError<PropagationError<Idx>>) -> Self {
+    match value {
+      WorkerBagError::WorkerError(e) => e,
+      WorkerBagError::BagFullError(e) => AC3ErrorKind::BufferFilled(e).into(),
+    }
+  }
+}
@@ -3,18 +3,103 @@ use space::Space;
 mod domain;
 pub use domain::Domain;
 mod errors;
-pub use errors::{AC3Error, AC3ErrorKind};
+pub use errors::{AC3Error, AC3ErrorKind, PropagationError};
 mod constraint;
 pub use constraint::Constraint;
 
 use crate::{grid::Grid, utility::WorkerBag};
-use std::hash::Hash;
+use std::{collections::HashSet, hash::Hash, sync::RwLock};
 
 pub type CSPDomains<const N: usize, Idx> = Space<Idx, Domain<N>>;
 
+/// A summary of the changes made whilst propagating tile removals
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Propagation<Idx: Hash + Eq> {
+  /// The number of propagation tasks run
+  pub tasks: usize,
+  /// Every cell that had tiles removed from its domain, or was inserted
+  pub changed: HashSet<Idx>,
+}
+
+impl<const N: usize, Idx: Clone + Ord> CSPDomains<N, Idx> {
+  /// Extracts the tile assigned to every cell, ordered by index.<br>
+  /// Returns `None` if any of the domains haven't been collapsed to one tile.
+  pub fn assignment(&self) -> Option<Vec<(Idx, usize)>> {
+    if !self.all(|d| d.is_single()) {
+      return None;
+    }
+
+    let mut assignment = self.collect_key_func(|d| d.single_item())?;
+    assignment.sort();
+    Some(assignment)
+  }
+}
+
+/// Propagates the removal of tiles through the domains, until every domain
+/// only contains tiles supported by its neighbours.
+///
+/// Each update is of the form `(idx, side, tile)`, representing that `tile`
+/// was removed from the cell whose neighbour on `side` is at `idx`.
+///
+/// Returns the number of propagation tasks run and the cells changed by them,
+/// or an error if:
+/// - Propagation leads to a contradiction (an empty domain), in which case the
+///   error records where the domain was wiped out
+/// - Propagation overflows the task buffer used
+pub fn propagate<const N: usize, Idx>(
+  domains: &CSPDomains<N, Idx>,
+  domain_size: usize,
+  grid: &(impl Grid<N, Idx> + Send + Sync),
+  constraint: &Constraint<N>,
+  updates: Vec<(Idx, usize, usize)>,
+) -> Result<Propagation<Idx>, PropagationError<Idx>>
+where
+  Idx: Hash + Eq + Clone + Send + Sync,
+{
+  let domain_hint = Domain::constraint(constraint, domain_size);
+  let workers: WorkerBag<(Idx, usize, usize)> = Default::default();
+  let changed = RwLock::new(HashSet::new());
+
+  workers.run_on(updates, |(idx, side, tile)| {
+    let inserted = !domains.exists(&idx);
+    domains.or_insert_at(&idx, domain_hint.clone());
+    // the side of this neighbour that faces the cell `tile` was removed from
+    let side = grid.opposite(side);
+
+    // calculate tiles that lost their last support from `tile`
+    // @note this will update the domain by removing those tiles
+    let tiles_removed: Vec<_> = (0..domain_size)
+      .filter(|&tile1| {
+        constraint[(tile1, tile, side)]
+          && domains
+            .write_at(&idx, |d| d.remove_side(tile1, side))
+            .unwrap()
+      })
+      .collect();
+    if inserted || !tiles_removed.is_empty() {
+      changed.write().unwrap().insert(idx.clone());
+    }
+
+    if domains.read_at(&idx, |d| d.is_empty()).unwrap() {
+      return Err(PropagationError {
+        kind: AC3ErrorKind::InconsistentChoice,
+        wipeout: Some((idx, side)),
+      });
+    }
+    Ok(grid.updates_for(&idx, tiles_removed))
+  })?;
+
+  Ok(Propagation {
+    tasks: workers.completed(),
+    changed: changed.into_inner().unwrap(),
+  })
+}
+
 /// Runs the AC3 constraint satisfaction algorithm.
 ///
-/// This restricts domains to only tiles consistent with the given constraint
+/// This restricts domains to only tiles consistent with the given constraint,
+/// returning the restricted domains alongside a summary of the propagation
+/// that restricted them (including the `start` cell).
 ///
 /// Will return an error if:
 /// - The initial tile restriction is not within the domain
@@ -27,42 +112,33 @@ pub fn ac3<const N: usize, Idx>(
   constraint: &Constraint<N>,
   start: &Idx,
   item: usize,
-) -> Result<CSPDomains<N, Idx>, AC3ErrorKind>
+) -> Result<(CSPDomains<N, Idx>, Propagation<Idx>), PropagationError<Idx>>
 where
   Idx: Hash + Eq + Clone + Send + Sync,
 {
-  let domain_hint = Domain::constraint(constraint, domain_size);
-  let workers: WorkerBag<(Idx, usize, usize)> = Default::default();
-
   if domains.exists(start) && !domains.read_at(start, |d| d.contains(item)).unwrap() {
-    return Err(AC3ErrorKind::InvalidChoice);
+    return Err(AC3ErrorKind::InvalidChoice.into());
   }
 
-  domains.or_insert_at(start, domain_hint.clone());
-  let to_remove = domains
+  // keep the side counts for `item`, so that it can be removed by propagation
+  domains.or_insert_at(start, Domain::constraint(constraint, domain_size));
+  let to_remove: Vec<_> = domains
     .write_at(start, |d| {
-      std::mem::replace(d, Domain::single(item, domain_size))
-        .into_iter()
-        .filter(|&tile| tile != item)
+      let to_remove: Vec<_> = d.iter().filter(|&tile| tile != item).collect();
+      to_remove.iter().for_each(|&tile| {
+        d.remove_item(tile);
+      });
+      to_remove
     })
     .unwrap();
 
-  workers.run_on(grid.updates_for(start, to_remove), |(idx, side, tile)| {
-    domains.or_insert_at(&idx, domain_hint.clone());
-
-    // calculate tiles to be removed from this neighbour
-    // @note this will update the domain by removing those tiles
-    let tiles_removed = (0..domain_size).filter(|&tile1| {
-      constraint[(tile, tile1, side)]
-        && domains
-          .write_at(&idx, |d| d.remove_side(tile, side))
-          .unwrap()
-    });
-
-    (domains.read_at(&idx, |d| !d.is_empty()).unwrap())
-      .then_some(grid.updates_for(&idx, tiles_removed))
-      .ok_or(AC3ErrorKind::InconsistentChoice)
-  })?;
-
-  Ok(domains)
+  let mut propagation = propagate(
+    &domains,
+    domain_size,
+    grid,
+    constraint,
+    grid.updates_for(start, to_remove),
+  )?;
+  propagation.changed.insert(start.clone());
+  Ok((domains, propagation))
 }
@@ -1,4 +1,16 @@
-use std::{collections::HashMap, hash::Hash, ops::Deref, sync::RwLock};
+use crate::utility::StableHasher;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+  collections::HashMap,
+  hash::{BuildHasherDefault, Hash},
+  ops::Deref,
+  sync::RwLock,
+};
+
+/// Hashes without any random state and with a fixed algorithm, so that a space
+/// built by the same inserts is iterated in the same order from run to run
+type BuildStableHasher = BuildHasherDefault<StableHasher>;
 
 /// An N dimensional space, intended to be used by multiple threads at once
 ///
@@ -6,7 +18,7 @@ use std::{collections::HashMap, hash::Hash, ops::Deref, sync::RwLock};
 /// - the outer lock, solely used for inserting values into an empty entry
 /// - the inner lock, used for in place modification + reading of values
 #[derive(Debug)]
-pub struct Space<Idx, T>(RwLock<HashMap<Idx, RwLock<T>>>);
+pub struct Space<Idx, T>(RwLock<HashMap<Idx, RwLock<T>, BuildStableHasher>>);
 
 impl<Idx: Clone + Hash + Eq, T: Clone> Clone for Space<Idx, T> {
   fn clone(&self) -> Self {
@@ -22,9 +34,20 @@ impl<Idx: Clone + Hash + Eq, T: Clone> Clone for Space<Idx, T> {
   }
 }
 
+impl<Idx: Hash + Eq, T> FromIterator<(Idx, T)> for Space<Idx, T> {
+  fn from_iter<I: IntoIterator<Item = (Idx, T)>>(iter: I) -> Self {
+    Self(RwLock::new(
+      iter
+        .into_iter()
+        .map(|(idx, item)| (idx, RwLock::new(item)))
+        .collect(),
+    ))
+  }
+}
+
 impl<Idx, T> Default for Space<Idx, T> {
   fn default() -> Self {
-    Self(RwLock::new(HashMap::new()))
+    Self(RwLock::new(HashMap::default()))
   }
 }
 
@@ -59,7 +82,7 @@ impl<Idx: Clone, T> Space<Idx, T> {
       .read()
       .unwrap()
       .iter()
-      .all(|(_, lock)| lock.read().map_or(false, |item| pred(&item)))
+      .all(|(_, lock)| lock.read().is_ok_and(|item| pred(&item)))
   }
 
   pub fn keys(&self) -> Vec<Idx> {
@@ -83,10 +106,7 @@ impl<Idx: Clone, T> Space<Idx, T> {
 
 impl<Idx: Hash + Eq, T> Space<Idx, T> {
   pub fn exists(&self, idx: &Idx) -> bool {
-    self
-      .0
-      .read()
-      .map_or(false, |hashmap| hashmap.contains_key(idx))
+    self.0.read().is_ok_and(|hashmap| hashmap.contains_key(idx))
   }
 
   pub fn read_at<R>(&self, idx: &Idx, mut op: impl FnMut(&T) -> R) -> Option<R> {
@@ -103,3 +123,27 @@ impl<Idx: Hash + Eq, T> Space<Idx, T> {
     Some(op(&mut item))
   }
 }
+
+#[cfg(feature = "serde")]
+impl<Idx: Serialize, T: Serialize> Serialize for Space<Idx, T> {
+  fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+    let hashmap = self.0.read().unwrap();
+    let entries: Vec<_> = hashmap
+      .iter()
+      .map(|(idx, rwlock)| (idx, rwlock.read().unwrap()))
+      .collect();
+    serializer.collect_seq(entries.iter().map(|(idx, item)| (idx, item.deref())))
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Idx, T> Deserialize<'de> for Space<Idx, T>
+where
+  Idx: Deserialize<'de> + Hash + Eq,
+  T: Deserialize<'de>,
+{
+  fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    let entries = Vec::<(Idx, T)>::deserialize(deserializer)?;
+    Ok(entries.into_iter().collect())
+  }
+}
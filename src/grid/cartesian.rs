@@ -3,6 +3,26 @@
 /// higher dimensions
 use super::Grid;
 
+/// Every index within the box from `start` (inclusive) to `end` (exclusive),
+/// ordered with the last axis changing fastest.
+pub fn cells_between<const D: usize>(start: [usize; D], end: [usize; D]) -> Vec<[usize; D]> {
+  if (0..D).any(|i| start[i] >= end[i]) {
+    return vec![];
+  }
+
+  let mut cells = vec![];
+  let mut idx = start;
+  loop {
+    cells.push(idx);
+    // increment the index like an odometer, from the last axis backwards
+    let Some(i) = (0..D).rev().find(|&i| idx[i] + 1 < end[i]) else {
+      return cells;
+    };
+    idx[i] += 1;
+    idx[i + 1..].copy_from_slice(&start[i + 1..]);
+  }
+}
+
 #[macro_export]
 macro_rules! cartesian_grid {
   ($name:ident, $ndims:literal) => {
@@ -10,7 +30,24 @@ macro_rules! cartesian_grid {
     ///
     /// The grid has a defined size, outside of which indices aren't defined
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-    pub struct $name([usize; $ndims]);
+    pub struct $name(pub [usize; $ndims]);
+
+    impl $name {
+      /// Every index within the grid
+      pub fn cells(&self) -> Vec<[usize; $ndims]> {
+        $crate::grid::cells_between([0; $ndims], self.0)
+      }
+
+      /// Every index within the box from `start` (inclusive) to `end`
+      /// (exclusive), clipped to the grid
+      pub fn cells_between(
+        &self,
+        start: [usize; $ndims],
+        end: [usize; $ndims],
+      ) -> Vec<[usize; $ndims]> {
+        $crate::grid::cells_between(start, std::array::from_fn(|i| end[i].min(self.0[i])))
+      }
+    }
 
     impl Grid<{ 2 * $ndims }, [usize; $ndims]> for $name {
       fn neighbours(&self, idx: &[usize; $ndims]) -> [Option<[usize; $ndims]>; 2 * $ndims] {
@@ -20,7 +57,7 @@ macro_rules! cartesian_grid {
         }
 
         for i in 0..$ndims {
-          if idx[i] > 1 {
+          if idx[i] > 0 {
             let mut n_idx = idx.clone();
             n_idx[i] -= 1;
             result[i] = Some(n_idx);
@@ -55,7 +92,24 @@ macro_rules! cartesian_wrapped_grid {
     /// The grid has a defined size, outside of which indices are wrapped around
     /// back around to the opposite edge
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-    pub struct $name([usize; $ndims]);
+    pub struct $name(pub [usize; $ndims]);
+
+    impl $name {
+      /// Every index within the grid
+      pub fn cells(&self) -> Vec<[usize; $ndims]> {
+        $crate::grid::cells_between([0; $ndims], self.0)
+      }
+
+      /// Every index within the box from `start` (inclusive) to `end`
+      /// (exclusive), clipped to the grid
+      pub fn cells_between(
+        &self,
+        start: [usize; $ndims],
+        end: [usize; $ndims],
+      ) -> Vec<[usize; $ndims]> {
+        $crate::grid::cells_between(start, std::array::from_fn(|i| end[i].min(self.0[i])))
+      }
+    }
 
     impl Grid<{ 2 * $ndims }, [usize; $ndims]> for $name {
       fn neighbours(&self, idx: &[usize; $ndims]) -> [Option<[usize; $ndims]>; 2 * $ndims] {
@@ -1,9 +1,9 @@
 mod cartesian;
 pub use cartesian::*;
 
-/// This is just a nice way of implementing arbitrary ND cartesian grids, but
-/// it can't be implemented until const parameters of the form `{2 * N}` are
-/// introduced, i.e. the `const_evaluatable_checked` feature.
+// This is just a nice way of implementing arbitrary ND cartesian grids, but
+// it can't be implemented until const parameters of the form `{2 * N}` are
+// introduced, i.e. the `const_evaluatable_checked` feature.
 // mod cartesian_const;
 // pub use cartesian_const::*;
 
@@ -21,6 +21,14 @@ pub use cartesian::*;
 pub trait Grid<const N: usize, Idx> {
   fn neighbours(&self, idx: &Idx) -> [Option<Idx>; N];
 
+  /// Finds the side of a neighbour that faces back towards the original index
+  ///
+  /// By default, this assumes the first half of the sides are directly
+  /// opposite the second half (as they are for cartesian grids).
+  fn opposite(&self, side: usize) -> usize {
+    (side + N / 2) % N
+  }
+
   /// Finds the updates to propagate for each direction in the given grid
   /// for a given collection of tiles changed
   fn updates_for(
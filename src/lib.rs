@@ -1,7 +1,9 @@
 pub mod consistency;
 pub mod grid;
+pub mod repair;
 pub mod sampling;
 pub mod search;
+pub mod selection;
 pub mod tiles;
 pub mod utility;
 
@@ -1,6 +1,13 @@
 pub use crate::{
   grid::*,
+  repair::{MinConflicts, RepairError},
   sampling::*,
-  search::{Backtrack, Naive, Restart, WFCError, WFCState},
+  search::{
+    count_solutions, AllSolutions, Backtrack, Beam, BestFirst, Naive, ParBacktrack,
+    PickActionError, Restart, ScoreBy, SolutionCount, Steps, WFCError, WFCEvent, WFCState,
+  },
+  selection::{
+    Combine, DomWdeg, Entropy, LeastConstraining, Mrv, MrvDegree, RandomOrder, Scanline, Spiral,
+  },
   tiles::{Direction, ImageEdge, ImageGrid, ImageSide, Tileable, Word, WordSide},
 };
@@ -0,0 +1,18 @@
+use std::fmt::{Debug, Display};
+
+/// An error produced when an assignment can't be repaired
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepairError<Idx> {
+  /// The tile assigned to the cell at the index isn't in the constraint
+  UnknownTile(Idx, usize),
+}
+
+impl<Idx: Debug> Display for RepairError<Idx> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::UnknownTile(idx, tile) => {
+        write!(f, "Tile #{tile} at {idx:?} isn't in the constraint")
+      }
+    }
+  }
+}
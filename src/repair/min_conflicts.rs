@@ -0,0 +1,235 @@
+use super::RepairError;
+use crate::{
+  consistency::{CSPDomains, Constraint, Domain},
+  grid::Grid,
+  sampling::{seeded_rng, CellSampler, SampleError},
+  selection::Cell,
+};
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+use std::{
+  collections::{HashMap, VecDeque},
+  hash::Hash,
+};
+
+/// The number of recent assignments that can't be undone by default
+const DEFAULT_TENURE: usize = 8;
+
+/// Repairs a complete, but invalid, assignment of tiles via local search.
+///
+/// Each step picks a random cell that conflicts with at least one of its
+/// neighbours and reassigns it to whichever tile conflicts with the fewest
+/// neighbours, with the sampler breaking ties between tiles.<br>
+/// The sampler sees each cell as able to take any tile, with every other cell
+/// collapsed to its assigned tile.
+///
+/// The cells that conflict are kept up to date as cells are reassigned, so each
+/// step only checks the reassigned cell and its neighbours.
+///
+/// To avoid cycling between the same few assignments, the tiles most recently
+/// replaced are kept in a tabu list and can't be reassigned to their cells,
+/// unless doing so would remove every conflict for that cell.
+pub struct MinConflicts<'a, const N: usize, Idx, G, S> {
+  /// The tile assigned to each cell, in the order they were given.
+  cells: Vec<(Idx, usize)>,
+  /// The position of each cell in `cells`.
+  positions: HashMap<Idx, usize>,
+  /// The tile assigned to each cell, as a collapsed domain for the sampler.
+  domains: CSPDomains<N, Idx>,
+  /// A domain with every tile, as any tile can be reassigned to a cell.
+  full: Domain<N>,
+  /// The positions of all cells that conflict with a neighbour.
+  conflicted: Vec<usize>,
+  /// Where each cell is in `conflicted`, if it conflicts with a neighbour.
+  slots: Vec<Option<usize>>,
+  /// The grid used, informs which cells are neighbours.
+  grid: &'a G,
+  /// A constraint on which tiles can be placed next to each other.
+  constraint: &'a Constraint<N>,
+
+  /// Breaks ties between equally good tiles.
+  sampler: S,
+  /// Picks which conflicting cell to reassign.
+  rng: ChaCha12Rng,
+  /// Recently replaced assignments, that shouldn't be reassigned.
+  tabu: VecDeque<(Idx, usize)>,
+  /// The maximum length of the tabu list.
+  tenure: usize,
+}
+
+impl<'a, const N: usize, Idx, G, S> MinConflicts<'a, N, Idx, G, S>
+where
+  Idx: Clone + Hash + Eq,
+  G: Grid<N, Idx>,
+  S: CellSampler<N, Idx>,
+{
+  /// Creates a repair of the given assignment, which fails if any of the
+  /// tiles assigned aren't in the constraint
+  pub fn new(
+    assignment: impl IntoIterator<Item = (Idx, usize)>,
+    grid: &'a G,
+    constraint: &'a Constraint<N>,
+    sampler: S,
+  ) -> Result<Self, RepairError<Idx>> {
+    let cells: Vec<_> = assignment.into_iter().collect();
+    if let Some((idx, tile)) = (cells.iter()).find(|(_, tile)| *tile >= constraint.no_tiles()) {
+      return Err(RepairError::UnknownTile(idx.clone(), *tile));
+    }
+
+    let no_tiles = constraint.no_tiles();
+    let mut full = Domain::constraint(constraint, no_tiles);
+    sampler.prepare(&mut full);
+    let mut repair = Self {
+      positions: (cells.iter().enumerate())
+        .map(|(i, (idx, _))| (idx.clone(), i))
+        .collect(),
+      domains: (cells.iter())
+        .map(|(idx, tile)| (idx.clone(), Domain::single(*tile, no_tiles)))
+        .collect(),
+      full,
+      conflicted: vec![],
+      slots: vec![None; cells.len()],
+      cells,
+      grid,
+      constraint,
+
+      sampler,
+      rng: seeded_rng(0),
+      tabu: VecDeque::new(),
+      tenure: DEFAULT_TENURE,
+    };
+    for i in 0..repair.cells.len() {
+      repair.recheck(i);
+    }
+    Ok(repair)
+  }
+
+  /// Sets the seed used to pick which conflicting cell to reassign
+  pub fn with_seed(mut self, seed: u64) -> Self {
+    self.rng = seeded_rng(seed);
+    self
+  }
+
+  /// Sets the number of recent assignments that can't be undone
+  pub fn with_tenure(mut self, tenure: usize) -> Self {
+    self.tenure = tenure;
+    self
+  }
+
+  /// The number of neighbours that would conflict with `tile` at `idx`
+  fn conflicts_at(&self, idx: &Idx, tile: usize) -> usize {
+    self
+      .grid
+      .neighbours(idx)
+      .into_iter()
+      .enumerate()
+      .filter_map(|(side, optn)| Some((side, *self.positions.get(&optn?)?)))
+      .filter(|&(side, i)| !self.constraint[(tile, self.cells[i].1, side)])
+      .count()
+  }
+
+  /// Adds the cell at position `i` to, or removes it from, the cells that
+  /// conflict with a neighbour
+  fn recheck(&mut self, i: usize) {
+    let (idx, tile) = &self.cells[i];
+    let conflicts = self.conflicts_at(idx, *tile) > 0;
+    match (self.slots[i], conflicts) {
+      (None, true) => {
+        self.slots[i] = Some(self.conflicted.len());
+        self.conflicted.push(i);
+      }
+      (Some(slot), false) => {
+        self.conflicted.swap_remove(slot);
+        if let Some(&moved) = self.conflicted.get(slot) {
+          self.slots[moved] = Some(slot);
+        }
+        self.slots[i] = None;
+      }
+      _ => {}
+    }
+  }
+
+  /// Whether no cells conflict with their neighbours
+  pub fn is_valid(&self) -> bool {
+    self.conflicted.is_empty()
+  }
+
+  /// Reassigns a single conflicting cell, returning the number of cells that
+  /// still conflict with their neighbours, or an error if the sampler fails.
+  pub fn step(&mut self) -> Result<usize, SampleError> {
+    if self.conflicted.is_empty() {
+      return Ok(0);
+    }
+
+    let i = self.conflicted[self.rng.gen_range(0..self.conflicted.len())];
+    let (idx, current) = self.cells[i].clone();
+    let conflicts: Vec<_> = (0..self.constraint.no_tiles())
+      .map(|tile| self.conflicts_at(&idx, tile))
+      .collect();
+
+    // only consider tabu tiles if they'd remove all conflicts
+    let allowed: Vec<_> = (0..conflicts.len())
+      .filter(|&tile| conflicts[tile] == 0 || !self.tabu.contains(&(idx.clone(), tile)))
+      .collect();
+    let allowed = match allowed.is_empty() {
+      true => (0..conflicts.len()).collect(),
+      false => allowed,
+    };
+
+    let fewest = allowed.iter().map(|&tile| conflicts[tile]).min().unwrap();
+    let candidates: Vec<_> = (allowed.into_iter())
+      .filter(|&tile| conflicts[tile] == fewest)
+      .collect();
+    let neighbours = self.grid.neighbours(&idx);
+    let cell = Cell {
+      idx: &idx,
+      domain: &self.full,
+      neighbours: &neighbours,
+      domains: &self.domains,
+      constraint: self.constraint,
+    };
+    let tile = self.sampler.sample_cell(&cell, &candidates)?;
+
+    // @note the tabu list ages even when the cell keeps its tile, otherwise
+    // every conflicting cell could be stuck behind its tabu tiles for good
+    // only the cell and its neighbours can have gained or lost conflicts
+    if tile != current {
+      let single = Domain::single(tile, self.constraint.no_tiles());
+      self.domains.write_at(&idx, |d| *d = single.clone());
+      self.cells[i].1 = tile;
+      self.recheck(i);
+      for neighbour in neighbours.iter().flatten() {
+        if let Some(&j) = self.positions.get(neighbour) {
+          self.recheck(j);
+        }
+      }
+      self.tabu.push_back((idx, current));
+    }
+    if tile == current || self.tabu.len() > self.tenure {
+      self.tabu.pop_front();
+    }
+
+    Ok(self.conflicted.len())
+  }
+
+  /// Repeatedly reassigns conflicting cells, until either no conflicts are
+  /// left or `max_steps` have been taken.<br>
+  /// Returns whether the assignment was fully repaired.
+  pub fn repair(&mut self, max_steps: usize) -> Result<bool, SampleError> {
+    for _ in 0..max_steps {
+      if self.step()? == 0 {
+        return Ok(true);
+      }
+    }
+    Ok(self.is_valid())
+  }
+
+  /// The tile assigned to each cell, in the order they were given
+  pub fn assignment(&self) -> &[(Idx, usize)] {
+    &self.cells
+  }
+
+  pub fn into_assignment(self) -> Vec<(Idx, usize)> {
+    self.cells
+  }
+}
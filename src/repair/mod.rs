@@ -0,0 +1,4 @@
+mod errors;
+pub use errors::RepairError;
+mod min_conflicts;
+pub use min_conflicts::MinConflicts;
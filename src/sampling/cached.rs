@@ -0,0 +1,126 @@
+use super::{errors::validate, seeded_rng, CellSampler, SampleError, WeightError};
+use crate::{consistency::Domain, selection::Cell};
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Samples entries in the same way as `Weighted`, but keeps the distribution
+/// over each cell's tiles within its domain, updated as tiles are removed.
+///
+/// Sampling every tile left in a cell finds the sampled tile in `O(log n)` time
+/// for `n` tiles, rather than summing their weights. Checking that the entries
+/// are every tile left still takes `O(n)` time, so this only saves looking up
+/// and summing weights. Any other selection of tiles (i.e. narrowed down by a
+/// value order) is sampled in `O(n)` time, by summing weights. Neither
+/// allocates.
+///
+/// The distribution is kept apart from the weights a selector gives domains
+/// (i.e. `Entropy`), so the two can use different weights.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CachedWeighted<R> {
+  rng: R,
+  weights: Arc<[f64]>,
+}
+
+impl<R: Rng> CachedWeighted<R> {
+  /// Creates a sampler from the weight of each entry, which must all be finite
+  /// and non-negative with at least one positive weight
+  pub fn new(rng: R, weights: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    let weights = weights.into();
+    validate(&weights)?;
+    Ok(Self { rng, weights })
+  }
+}
+
+impl CachedWeighted<ChaCha12Rng> {
+  /// Samples with a ChaCha rng seeded from `seed`
+  pub fn from_seed(seed: u64, weights: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    Self::new(seeded_rng(seed), weights)
+  }
+}
+
+impl<R> CachedWeighted<R> {
+  /// The weight of each entry
+  pub fn weights(&self) -> &Arc<[f64]> {
+    &self.weights
+  }
+
+  /// Whether the entries are exactly the tiles left in the domain, and the
+  /// domain is weighted for sampling by this sampler's weights, in `O(n)`
+  fn is_cached<const N: usize>(&self, domain: &Domain<N>, entries: &[usize]) -> bool {
+    // @note as the entries are strictly increasing they can't repeat, so they
+    // must be every tile left if there are as many of them
+    domain
+      .sampling_weights()
+      .is_some_and(|w| Arc::ptr_eq(w, &self.weights))
+      && entries.len() == domain.len()
+      && entries.windows(2).all(|pair| pair[0] < pair[1])
+      && entries.iter().all(|&tile| domain.contains(tile))
+  }
+}
+
+impl<R0, R1> PartialEq<CachedWeighted<R1>> for CachedWeighted<R0> {
+  fn eq(&self, other: &CachedWeighted<R1>) -> bool {
+    self.weights == other.weights
+  }
+}
+impl<R> Eq for CachedWeighted<R> {}
+
+impl<const N: usize, Idx, R: Rng + Clone> CellSampler<N, Idx> for CachedWeighted<R> {
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    if self.is_cached(cell.domain, entries) {
+      let total = cell.domain.total_weight().unwrap_or(0.0);
+      if total <= 0.0 {
+        return Err(SampleError::ZeroWeight);
+      }
+      if let Some(tile) = cell.domain.item_at_weight(self.rng.gen_range(0.0..total)) {
+        return Ok(tile);
+      }
+    }
+
+    if entries.is_empty() {
+      return Err(SampleError::Empty);
+    }
+    let mut total = 0.0;
+    for &tile in entries {
+      total += self
+        .weights
+        .get(tile)
+        .ok_or(SampleError::MissingWeight(tile))?;
+    }
+    if total <= 0.0 {
+      return Err(SampleError::ZeroWeight);
+    }
+
+    // @note the last tile with a positive weight is kept, in case the point
+    // lands past the end of the weights due to rounding
+    let mut point = self.rng.gen_range(0.0..total);
+    let mut last = None;
+    for &tile in entries {
+      let weight = self.weights[tile];
+      if weight > 0.0 {
+        last = Some(tile);
+        if point < weight {
+          return Ok(tile);
+        }
+        point -= weight;
+      }
+    }
+    last.ok_or(SampleError::ZeroWeight)
+  }
+
+  fn prepare(&self, domain: &mut Domain<N>) {
+    domain.set_sampling_weights(self.weights.clone());
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
+  }
+}
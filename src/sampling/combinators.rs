@@ -0,0 +1,359 @@
+//! Combinators that build samplers out of other samplers.
+//!
+//! Every combinator is `Clone` and a `CellSampler`, so they all plug straight
+//! into `WFCState`. Only `Mix` and `Filter` (with a predicate on the tile
+//! alone, see [`ByTile`]) are also a `Sampler`, as `PerRegion` and `Fixed` pick
+//! how to sample from the cell's index, which a `Sampler` isn't given.
+
+use super::{seeded_rng, CellSampler, SampleError, Sampler};
+use crate::{consistency::Domain, selection::Cell};
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, hash::Hash, sync::Arc};
+
+/// Samples with the first sampler with probability `p`, otherwise with the
+/// second sampler
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Mix<A, B, R> {
+  rng: R,
+  first: A,
+  second: B,
+  p: f64,
+}
+
+impl<A, B, R: Rng> Mix<A, B, R> {
+  pub fn new(rng: R, first: A, second: B, p: f64) -> Self {
+    Self {
+      rng,
+      first,
+      second,
+      p,
+    }
+  }
+}
+
+impl<A, B> Mix<A, B, ChaCha12Rng> {
+  /// Picks between samplers with a ChaCha rng seeded from `seed`
+  pub fn from_seed(seed: u64, first: A, second: B, p: f64) -> Self {
+    Self::new(seeded_rng(seed), first, second, p)
+  }
+}
+
+impl<const N: usize, Idx, A, B, R> CellSampler<N, Idx> for Mix<A, B, R>
+where
+  A: CellSampler<N, Idx>,
+  B: CellSampler<N, Idx>,
+  R: Rng + Clone,
+{
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    match self.rng.gen::<f64>() < self.p {
+      true => self.first.sample_cell(cell, entries),
+      false => self.second.sample_cell(cell, entries),
+    }
+  }
+
+  /// @note if both samplers prepare domains in the same way (i.e. both are
+  /// `CachedWeighted`), the second sampler's preparation is kept, so the first
+  /// sampler samples without its cache
+  fn prepare(&self, domain: &mut Domain<N>) {
+    self.first.prepare(domain);
+    self.second.prepare(domain);
+  }
+
+  /// @note a tile pinned by either sampler is pinned, preferring the first
+  fn pinned(&self, cell: &Cell<'_, N, Idx>) -> Option<usize> {
+    (self.first.pinned(cell)).or_else(|| self.second.pinned(cell))
+  }
+
+  fn collapsed(&mut self, idx: &Idx, tile: usize) {
+    self.first.collapsed(idx, tile);
+    self.second.collapsed(idx, tile);
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
+    self.first.restarted(&last.first);
+    self.second.restarted(&last.second);
+  }
+}
+
+impl<A, B, R> Sampler for Mix<A, B, R>
+where
+  A: Sampler,
+  B: Sampler,
+  R: Rng,
+{
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError> {
+    match self.rng.gen::<f64>() < self.p {
+      true => self.first.sample(entries),
+      false => self.second.sample(entries),
+    }
+  }
+
+  fn collapsed(&mut self, tile: usize) {
+    self.first.collapsed(tile);
+    self.second.collapsed(tile);
+  }
+}
+
+/// Which entries a `Filter` samples from, given the cell's index and the tile
+pub trait Predicate<Idx> {
+  fn passes(&self, idx: &Idx, tile: usize) -> bool;
+}
+
+impl<Idx, F: Fn(&Idx, usize) -> bool> Predicate<Idx> for F {
+  fn passes(&self, idx: &Idx, tile: usize) -> bool {
+    self(idx, tile)
+  }
+}
+
+/// A predicate on the tile alone, so that a `Filter` using it doesn't need the
+/// cell (see [`Filter::by_tile`])
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByTile<F>(pub F);
+
+impl<Idx, F: Fn(usize) -> bool> Predicate<Idx> for ByTile<F> {
+  fn passes(&self, _idx: &Idx, tile: usize) -> bool {
+    (self.0)(tile)
+  }
+}
+
+/// Samples only the entries that pass a predicate on the cell's index and the
+/// tile, unless none of them pass in which case every entry is sampled.
+///
+/// A filter with a predicate on the tile alone (see [`Filter::by_tile`]) is
+/// also a `Sampler`.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Filter<F, S> {
+  predicate: F,
+  inner: S,
+}
+
+impl<F, S> Filter<F, S> {
+  pub fn new(predicate: F, inner: S) -> Self {
+    Self { predicate, inner }
+  }
+
+  /// The entries that pass the predicate, or every entry if none of them pass
+  fn passed(&self, entries: &[usize], passes: impl Fn(&F, usize) -> bool) -> Vec<usize> {
+    let passed: Vec<_> = (entries.iter().copied())
+      .filter(|&tile| passes(&self.predicate, tile))
+      .collect();
+    match passed.is_empty() {
+      true => entries.to_vec(),
+      false => passed,
+    }
+  }
+}
+
+impl<F, S> Filter<ByTile<F>, S> {
+  /// Filters entries with a predicate on the tile alone
+  pub fn by_tile(predicate: F, inner: S) -> Self {
+    Self::new(ByTile(predicate), inner)
+  }
+}
+
+impl<const N: usize, Idx, F, S> CellSampler<N, Idx> for Filter<F, S>
+where
+  F: Predicate<Idx>,
+  S: CellSampler<N, Idx>,
+{
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    let passed = self.passed(entries, |predicate, tile| predicate.passes(cell.idx, tile));
+    self.inner.sample_cell(cell, &passed)
+  }
+
+  fn prepare(&self, domain: &mut Domain<N>) {
+    self.inner.prepare(domain);
+  }
+
+  fn pinned(&self, cell: &Cell<'_, N, Idx>) -> Option<usize> {
+    self.inner.pinned(cell)
+  }
+
+  fn collapsed(&mut self, idx: &Idx, tile: usize) {
+    self.inner.collapsed(idx, tile);
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.inner.restarted(&last.inner);
+  }
+}
+
+impl<F, S> Sampler for Filter<ByTile<F>, S>
+where
+  F: Fn(usize) -> bool,
+  S: Sampler,
+{
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError> {
+    let passed = self.passed(entries, |ByTile(predicate), tile| predicate(tile));
+    self.inner.sample(&passed)
+  }
+
+  fn collapsed(&mut self, tile: usize) {
+    self.inner.collapsed(tile);
+  }
+}
+
+/// Samples with a different sampler in each region of the grid, where each
+/// cell's region is found from its index.<br>
+/// Cells in regions without a sampler use the default sampler.
+///
+/// @note as regions are found from the cell's index, this isn't a `Sampler`
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  feature = "serde",
+  serde(bound(
+    serialize = "K: Serialize + Hash + Eq, S: Serialize, F: Serialize",
+    deserialize = "K: Deserialize<'de> + Hash + Eq, S: Deserialize<'de>, F: Deserialize<'de>"
+  ))
+)]
+pub struct PerRegion<K, S, F> {
+  region: F,
+  samplers: HashMap<K, S>,
+  default: S,
+}
+
+impl<K: Hash + Eq, S, F> PerRegion<K, S, F> {
+  pub fn new(region: F, default: S) -> Self {
+    Self {
+      region,
+      samplers: HashMap::new(),
+      default,
+    }
+  }
+
+  /// Samples cells in a region with the given sampler
+  pub fn with_sampler(mut self, region: K, sampler: S) -> Self {
+    self.samplers.insert(region, sampler);
+    self
+  }
+}
+
+impl<const N: usize, Idx, K, S, F> CellSampler<N, Idx> for PerRegion<K, S, F>
+where
+  K: Hash + Eq,
+  S: CellSampler<N, Idx>,
+  F: Fn(&Idx) -> K,
+{
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    let region = (self.region)(cell.idx);
+    (self.samplers.get_mut(&region))
+      .unwrap_or(&mut self.default)
+      .sample_cell(cell, entries)
+  }
+
+  /// @note domains don't know which region they're in, so only the default
+  /// sampler prepares them, and the samplers for other regions sample without
+  /// anything they'd prepare (i.e. `CachedWeighted` samples without its cache)
+  fn prepare(&self, domain: &mut Domain<N>) {
+    self.default.prepare(domain);
+  }
+
+  fn pinned(&self, cell: &Cell<'_, N, Idx>) -> Option<usize> {
+    let region = (self.region)(cell.idx);
+    (self.samplers.get(&region))
+      .unwrap_or(&self.default)
+      .pinned(cell)
+  }
+
+  fn collapsed(&mut self, idx: &Idx, tile: usize) {
+    let region = (self.region)(idx);
+    (self.samplers.get_mut(&region))
+      .unwrap_or(&mut self.default)
+      .collapsed(idx, tile);
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.default.restarted(&last.default);
+    for (region, sampler) in self.samplers.iter_mut() {
+      if let Some(last) = last.samplers.get(region) {
+        sampler.restarted(last);
+      }
+    }
+  }
+}
+
+/// Forces specific cells to be collapsed to specific tiles, sampling every
+/// other cell with the inner sampler.
+///
+/// Forced tiles are pinned, so they're picked even if a value order would have
+/// narrowed them away, and any action that removes a forced tile from its cell
+/// (i.e. by propagation) is rejected so that the search backtracks. If a forced
+/// tile has already been removed from its cell, sampling fails.
+///
+/// @note as tiles are forced by the cell's index, this isn't a `Sampler`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  feature = "serde",
+  serde(bound(
+    serialize = "Idx: Serialize + Hash + Eq, S: Serialize",
+    deserialize = "Idx: Deserialize<'de> + Hash + Eq, S: Deserialize<'de>"
+  ))
+)]
+pub struct Fixed<Idx, S> {
+  overrides: Arc<HashMap<Idx, usize>>,
+  inner: S,
+}
+
+impl<Idx: Hash + Eq, S> Fixed<Idx, S> {
+  pub fn new(overrides: impl IntoIterator<Item = (Idx, usize)>, inner: S) -> Self {
+    Self {
+      overrides: Arc::new(overrides.into_iter().collect()),
+      inner,
+    }
+  }
+}
+
+impl<const N: usize, Idx, S> CellSampler<N, Idx> for Fixed<Idx, S>
+where
+  Idx: Hash + Eq,
+  S: CellSampler<N, Idx>,
+{
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    match self.overrides.get(cell.idx) {
+      None => self.inner.sample_cell(cell, entries),
+      Some(&tile) if entries.contains(&tile) || cell.domain.contains(tile) => Ok(tile),
+      Some(&tile) => Err(SampleError::Unavailable(tile)),
+    }
+  }
+
+  fn pinned(&self, cell: &Cell<'_, N, Idx>) -> Option<usize> {
+    self.overrides.get(cell.idx).copied()
+  }
+
+  fn prepare(&self, domain: &mut Domain<N>) {
+    self.inner.prepare(domain);
+  }
+
+  fn collapsed(&mut self, idx: &Idx, tile: usize) {
+    self.inner.collapsed(idx, tile);
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.inner.restarted(&last.inner);
+  }
+}
@@ -0,0 +1,76 @@
+use std::fmt::Display;
+
+/// An error produced when weights can't be sampled from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WeightError {
+  /// The weight of the tile at the index is negative
+  Negative(usize),
+  /// The weight of the tile at the index is infinite or NaN
+  NotFinite(usize),
+  /// None of the weights are positive
+  NoPositive,
+  /// No tile has the given name
+  UnknownName(String),
+  /// The tile is past the end of the tiles being weighted
+  UnknownTile(usize),
+  /// The side is past the number of sides each cell has
+  UnknownSide(usize),
+}
+
+impl Display for WeightError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Negative(tile) => write!(f, "The weight of tile #{tile} is negative"),
+      Self::NotFinite(tile) => write!(f, "The weight of tile #{tile} isn't finite"),
+      Self::NoPositive => write!(f, "There are no tiles with a positive weight"),
+      Self::UnknownName(name) => write!(f, "There is no tile named {name:?}"),
+      Self::UnknownTile(tile) => write!(f, "There is no tile #{tile}"),
+      Self::UnknownSide(side) => write!(f, "There is no side #{side}"),
+    }
+  }
+}
+
+/// An error produced when an entry can't be sampled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SampleError {
+  /// There were no entries to sample from
+  Empty,
+  /// There's no weight for the tile
+  MissingWeight(usize),
+  /// The weight of the tile is negative, infinite or NaN
+  InvalidWeight(usize),
+  /// None of the entries have a positive weight
+  ZeroWeight,
+  /// The tile has to be sampled, but isn't one of the entries
+  Unavailable(usize),
+}
+
+impl Display for SampleError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Empty => write!(f, "There are no tiles to sample from"),
+      Self::MissingWeight(tile) => write!(f, "There's no weight for tile #{tile}"),
+      Self::InvalidWeight(tile) => write!(f, "The weight of tile #{tile} is invalid"),
+      Self::ZeroWeight => write!(f, "None of the tiles have a positive weight"),
+      Self::Unavailable(tile) => write!(f, "Tile #{tile} has to be picked, but can't be"),
+    }
+  }
+}
+
+/// Checks that every weight is finite and non-negative, and that at least one
+/// weight is positive
+pub(crate) fn validate(weights: &[f64]) -> Result<(), WeightError> {
+  for (tile, &weight) in weights.iter().enumerate() {
+    if !weight.is_finite() {
+      return Err(WeightError::NotFinite(tile));
+    }
+    if weight < 0.0 {
+      return Err(WeightError::Negative(tile));
+    }
+  }
+
+  match weights.iter().any(|&weight| weight > 0.0) {
+    true => Ok(()),
+    false => Err(WeightError::NoPositive),
+  }
+}
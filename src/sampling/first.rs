@@ -1,15 +1,25 @@
-use super::Sampler;
+use super::{CellSampler, SampleError, Sampler};
+use crate::selection::Cell;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Takes the first entry from the given collection
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct First;
 
 impl Sampler for First {
-  fn sample(&mut self, entries: &[usize]) -> usize {
-    assert!(
-      !entries.is_empty(),
-      "Should sample from a non-empty collection"
-    );
-    entries[0]
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError> {
+    entries.first().copied().ok_or(SampleError::Empty)
+  }
+}
+
+impl<const N: usize, Idx> CellSampler<N, Idx> for First {
+  fn sample_cell(
+    &mut self,
+    _cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    self.sample(entries)
   }
 }
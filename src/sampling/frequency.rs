@@ -0,0 +1,114 @@
+use super::{
+  errors::validate, seeded_rng, weighted::sample_weighted, CellSampler, SampleError, Sampler,
+  WeightError,
+};
+use crate::selection::Cell;
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Samples tiles so that the proportion of each tile placed converges on a
+/// target proportion, by counting the tiles placed so far.
+///
+/// Each entry is weighted by its target proportion `p`, scaled by how far
+/// behind (or ahead of) its target it is:
+/// `w = p * ((p * placed + 1) / (count + 1)) ^ strength`
+///
+/// The counts are kept within the sampler, so they're cloned into each new
+/// state and discarded alongside it when backtracking. Every cell collapsed by
+/// an action is counted in the state it creates, including cells forced by
+/// propagation, so tiles sampled by a state that are then rejected aren't
+/// counted.
+///
+/// @note cells reset by `reset_region` are still counted
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Frequency<R> {
+  rng: R,
+  targets: Arc<[f64]>,
+  strength: f64,
+  counts: Vec<usize>,
+  placed: usize,
+}
+
+impl<R: Rng> Frequency<R> {
+  /// Creates a sampler from the target proportion of each tile, which are
+  /// normalised so they don't need to sum to `1`
+  pub fn new(rng: R, targets: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    let targets: Arc<[f64]> = targets.into();
+    validate(&targets)?;
+
+    let total: f64 = targets.iter().sum();
+    Ok(Self {
+      rng,
+      counts: vec![0; targets.len()],
+      targets: targets.iter().map(|target| target / total).collect(),
+      strength: 1.0,
+      placed: 0,
+    })
+  }
+}
+
+impl Frequency<ChaCha12Rng> {
+  /// Samples with a ChaCha rng seeded from `seed`
+  pub fn from_seed(seed: u64, targets: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    Self::new(seeded_rng(seed), targets)
+  }
+}
+
+impl<R> Frequency<R> {
+  /// Sets how strongly tiles are pushed towards their target proportion, by
+  /// default `1`.<br>
+  /// At `0` this is the same as weighted sampling by the target proportions.
+  pub fn with_strength(self, strength: f64) -> Self {
+    Self { strength, ..self }
+  }
+
+  /// The target proportion of each tile, summing to `1`
+  pub fn targets(&self) -> &[f64] {
+    &self.targets
+  }
+
+  /// The number of each tile placed so far
+  pub fn counts(&self) -> &[usize] {
+    &self.counts
+  }
+}
+
+impl<R: Rng> Sampler for Frequency<R> {
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError> {
+    let placed = self.placed as f64;
+    sample_weighted(&mut self.rng, entries, |tile| {
+      let target = self.targets.get(tile)?;
+      let behind = (target * placed + 1.0) / (self.counts[tile] as f64 + 1.0);
+      Some(target * behind.powf(self.strength))
+    })
+  }
+
+  fn collapsed(&mut self, tile: usize) {
+    if let Some(count) = self.counts.get_mut(tile) {
+      *count += 1;
+      self.placed += 1;
+    }
+  }
+}
+
+impl<const N: usize, Idx, R: Rng + Clone> CellSampler<N, Idx> for Frequency<R> {
+  fn sample_cell(
+    &mut self,
+    _cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    self.sample(entries)
+  }
+
+  fn collapsed(&mut self, _idx: &Idx, tile: usize) {
+    Sampler::collapsed(self, tile);
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
+  }
+}
@@ -0,0 +1,240 @@
+use super::{
+  errors::validate, seeded_rng, weighted::sample_weighted, CellSampler, SampleError, WeightError,
+};
+use crate::{grid::Grid, selection::Cell};
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, hash::Hash, sync::Arc};
+
+/// Weights each entry by how well it fits with the neighbours that have already
+/// been collapsed, as soft preferences on top of the constraint (i.e. grass is
+/// usually next to grass).
+///
+/// Each tile has a base weight, which is multiplied by its affinity with each
+/// collapsed neighbour. The affinity of a tile with a neighbouring tile on a
+/// side (the side of the tile facing the neighbour) is `1` unless it's set by
+/// hand or learnt from an example.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Markov<const N: usize, R> {
+  rng: R,
+  weights: Arc<[f64]>,
+  /// The affinity of each tile with each neighbouring tile, on each side
+  affinities: Arc<[f64]>,
+}
+
+impl<const N: usize, R: Rng> Markov<N, R> {
+  /// Creates a sampler from the base weight of each tile, which must all be
+  /// finite and non-negative with at least one positive weight
+  pub fn new(rng: R, weights: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    let weights: Arc<[f64]> = weights.into();
+    validate(&weights)?;
+
+    let no_tiles = weights.len();
+    Ok(Self {
+      rng,
+      weights,
+      affinities: vec![1.0; N * no_tiles * no_tiles].into(),
+    })
+  }
+
+  /// Creates a sampler from an example of which tile is at each index in a
+  /// grid, weighting each tile by how often it appears in the example and
+  /// learning the affinities between tiles from how often they're neighbours.
+  ///
+  /// Fails if any tile in the example isn't less than `no_tiles`.
+  pub fn from_example<Idx, G>(
+    rng: R,
+    no_tiles: usize,
+    grid: &G,
+    example: impl IntoIterator<Item = (Idx, usize)>,
+  ) -> Result<Self, WeightError>
+  where
+    Idx: Hash + Eq,
+    G: Grid<N, Idx>,
+  {
+    let example: HashMap<_, _> = example.into_iter().collect();
+    let mut weights = vec![0.0; no_tiles];
+    for &tile in example.values() {
+      *weights
+        .get_mut(tile)
+        .ok_or(WeightError::UnknownTile(tile))? += 1.0;
+    }
+
+    let mut markov = Self::new(rng, weights)?;
+    markov.learn(grid, example)?;
+    Ok(markov)
+  }
+}
+
+impl<const N: usize> Markov<N, ChaCha12Rng> {
+  /// Samples with a ChaCha rng seeded from `seed`
+  pub fn from_seed(seed: u64, weights: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    Self::new(seeded_rng(seed), weights)
+  }
+}
+
+impl<const N: usize, R> Markov<N, R> {
+  /// The base weight of each tile
+  pub fn weights(&self) -> &Arc<[f64]> {
+    &self.weights
+  }
+
+  fn no_tiles(&self) -> usize {
+    self.weights.len()
+  }
+
+  fn position(&self, tile: usize, neighbour: usize, side: usize) -> usize {
+    position(self.no_tiles(), tile, neighbour, side)
+  }
+
+  /// Checks that a tile is one of the tiles being weighted
+  fn check_tile(&self, tile: usize) -> Result<(), WeightError> {
+    match tile < self.no_tiles() {
+      true => Ok(()),
+      false => Err(WeightError::UnknownTile(tile)),
+    }
+  }
+
+  /// How strongly a tile prefers to have the neighbouring tile on a side, for
+  /// tiles that are being weighted and one of the `N` sides
+  pub fn affinity(&self, tile: usize, neighbour: usize, side: usize) -> Result<f64, WeightError> {
+    self.check_tile(tile)?;
+    self.check_tile(neighbour)?;
+    if side >= N {
+      return Err(WeightError::UnknownSide(side));
+    }
+    Ok(self.affinities[self.position(tile, neighbour, side)])
+  }
+
+  /// Sets how strongly a tile prefers to have the neighbouring tile on a side,
+  /// which must be finite and non-negative, for tiles that are being weighted
+  /// and one of the `N` sides.
+  ///
+  /// @note this only sets the affinity for one side of the pair of tiles, the
+  /// neighbouring tile's affinity with the tile on the opposite side is left
+  /// alone
+  pub fn set_affinity(
+    &mut self,
+    tile: usize,
+    neighbour: usize,
+    side: usize,
+    affinity: f64,
+  ) -> Result<(), WeightError> {
+    self.check_tile(tile)?;
+    self.check_tile(neighbour)?;
+    if side >= N {
+      return Err(WeightError::UnknownSide(side));
+    }
+    if !affinity.is_finite() {
+      return Err(WeightError::NotFinite(tile));
+    }
+    if affinity < 0.0 {
+      return Err(WeightError::Negative(tile));
+    }
+
+    let position = self.position(tile, neighbour, side);
+    Arc::make_mut(&mut self.affinities)[position] = affinity;
+    Ok(())
+  }
+
+  /// Replaces every affinity with those learnt from an example of which tile
+  /// is at each index in a grid.
+  ///
+  /// The affinity of a tile with a neighbour is how much more likely the tile
+  /// is to be next to the neighbour than it is to appear at all, smoothed so
+  /// that pairs missing from the example are unlikely rather than impossible.
+  ///
+  /// Fails if any tile in the example isn't being weighted, leaving the
+  /// affinities alone.
+  pub fn learn<Idx, G>(
+    &mut self,
+    grid: &G,
+    example: impl IntoIterator<Item = (Idx, usize)>,
+  ) -> Result<(), WeightError>
+  where
+    Idx: Hash + Eq,
+    G: Grid<N, Idx>,
+  {
+    let no_tiles = self.no_tiles();
+    let example: HashMap<_, _> = example.into_iter().collect();
+    for &tile in example.values() {
+      self.check_tile(tile)?;
+    }
+
+    let mut counts = vec![0.0; no_tiles];
+    let mut pairs = vec![0.0; N * no_tiles * no_tiles];
+    for (idx, &tile) in example.iter() {
+      counts[tile] += 1.0;
+      for (side, optn) in grid.neighbours(idx).into_iter().enumerate() {
+        if let Some(&neighbour) = optn.and_then(|idx| example.get(&idx)) {
+          pairs[self.position(tile, neighbour, side)] += 1.0;
+        }
+      }
+    }
+
+    let smoothing = no_tiles as f64;
+    let total = example.len() as f64 + smoothing;
+    let mut affinities = vec![1.0; pairs.len()];
+    for side in 0..N {
+      for neighbour in 0..no_tiles {
+        let positions = (0..no_tiles).map(|tile| self.position(tile, neighbour, side));
+        let next_to: f64 = positions.map(|i| pairs[i]).sum::<f64>() + smoothing;
+        for (tile, count) in counts.iter().enumerate() {
+          let position = self.position(tile, neighbour, side);
+          let given_neighbour = (pairs[position] + 1.0) / next_to;
+          let at_all = (count + 1.0) / total;
+          affinities[position] = given_neighbour / at_all;
+        }
+      }
+    }
+    self.affinities = affinities.into();
+    Ok(())
+  }
+}
+
+impl<const N: usize, Idx, R> CellSampler<N, Idx> for Markov<N, R>
+where
+  Idx: Hash + Eq,
+  R: Rng + Clone,
+{
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    // @note a neighbour may have been collapsed to a tile that isn't weighted
+    // (i.e. by another sampler), which has no affinity with any tile
+    let collapsed = (0..N)
+      .filter_map(|side| Some((side, cell.neighbour(side, |d| d.single_item())??)))
+      .map(|(side, neighbour)| match neighbour < self.weights.len() {
+        true => Ok((side, neighbour)),
+        false => Err(SampleError::MissingWeight(neighbour)),
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let Self {
+      rng,
+      weights,
+      affinities,
+    } = self;
+    sample_weighted(rng, entries, |tile| {
+      let weight = weights.get(tile)?;
+      let affinity: f64 = (collapsed.iter())
+        .map(|&(side, neighbour)| affinities[position(weights.len(), tile, neighbour, side)])
+        .product();
+      Some(weight * affinity)
+    })
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
+  }
+}
+
+/// The position of the affinity of a tile with a neighbouring tile on a side
+fn position(no_tiles: usize, tile: usize, neighbour: usize, side: usize) -> usize {
+  (side * no_tiles + tile) * no_tiles + neighbour
+}
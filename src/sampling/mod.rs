@@ -2,13 +2,89 @@
 /// like those from the `rand` crate, but I can't for two main reasons:
 /// - I'd like to make `Clone` easier to use for samplers
 /// - Sampling needs to be over a collection rather than on a RV
+mod cached;
+pub use cached::CachedWeighted;
+mod combinators;
+pub use combinators::{ByTile, Filter, Fixed, Mix, PerRegion, Predicate};
+mod errors;
+pub use errors::{SampleError, WeightError};
 mod first;
 pub use first::First;
+mod frequency;
+pub use frequency::Frequency;
+mod markov;
+pub use markov::Markov;
+mod positional;
+pub use positional::Positional;
+mod temperature;
+pub use temperature::{Schedule, Temperature};
 mod uniform;
 pub use uniform::Uniform;
 mod weighted;
 pub use weighted::Weighted;
 
+use crate::{consistency::Domain, selection::Cell};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+/// The rng used by every sampler created from a seed, so that the same seed
+/// will always give the same samples.
+///
+/// This is a `ChaCha12Rng` rather than a `StdRng`, as `StdRng` isn't portable
+/// between versions of `rand` and can't be serialised, so seeded samplers
+/// couldn't be checkpointed.
+pub(crate) fn seeded_rng(seed: u64) -> ChaCha12Rng {
+  ChaCha12Rng::seed_from_u64(seed)
+}
+
 pub trait Sampler {
-  fn sample(&mut self, entries: &[usize]) -> usize;
+  /// Picks one of the entries, or fails if none of them can be picked
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError>;
+
+  /// Called on the sampler of a new state for each cell collapsed by the
+  /// action that created it, whether the tile was sampled or forced by
+  /// propagation (i.e. to count the tiles placed so far).
+  fn collapsed(&mut self, _tile: usize) {}
+}
+
+/// Samples the tile a particular cell should be collapsed to, with access to
+/// where the cell is and what its neighbours can still be (i.e. to vary the
+/// distribution of tiles across a map).
+///
+/// Samplers that don't need the cell (i.e. `First` or `Uniform`) implement both
+/// this and `Sampler`, sampling a cell by ignoring it.
+pub trait CellSampler<const N: usize, Idx> {
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError>;
+
+  /// Prepares a domain before it's sampled from by this sampler, i.e. to keep
+  /// track of anything needed to sample from it as tiles are removed.
+  fn prepare(&self, _domain: &mut Domain<N>) {}
+
+  /// The tile a cell has to be collapsed to, if any, which is picked as is
+  /// rather than ordering and sampling the cell's tiles (so that a value order
+  /// can't narrow it away).
+  ///
+  /// `WFCState` rejects any action that removes a pinned tile from its cell,
+  /// including by propagation.
+  fn pinned(&self, _cell: &Cell<'_, N, Idx>) -> Option<usize> {
+    None
+  }
+
+  /// Called on the sampler of a new state for each cell collapsed by the
+  /// action that created it, whether the tile was sampled or forced by
+  /// propagation (i.e. to count the tiles placed so far).
+  fn collapsed(&mut self, _idx: &Idx, _tile: usize) {}
+
+  /// Called on the sampler of the state a search restarts from, with the
+  /// sampler the last attempt ended with (i.e. to carry a random number
+  /// generator forward, so that each attempt samples differently).
+  fn restarted(&mut self, _last: &Self)
+  where
+    Self: Sized,
+  {
+  }
 }
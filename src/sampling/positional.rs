@@ -0,0 +1,58 @@
+use super::{seeded_rng, weighted::sample_weighted, CellSampler, SampleError};
+use crate::selection::Cell;
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+
+/// Weights each entry by a function of the cell it's being sampled for, so that
+/// the distribution of tiles can vary across a map.
+///
+/// For example, to place tile `0` more often towards the right of a grid that's
+/// 8 cells wide (i.e. with weights interpolated from a density image):
+/// ```
+/// use wfc::prelude::*;
+///
+/// let density = |&[x, _]: &[usize; 2], tile: usize| match tile {
+///   0 => x as f64 / 7.0,
+///   _ => 1.0 - x as f64 / 7.0,
+/// };
+/// let sampler = Positional::from_seed(0, density);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Positional<R, F> {
+  rng: R,
+  weight: F,
+}
+
+impl<R: Rng, F> Positional<R, F> {
+  /// Creates a sampler from a function giving the weight of a tile at an
+  /// index, which should be finite and non-negative
+  pub fn new(rng: R, weight: F) -> Self {
+    Self { rng, weight }
+  }
+}
+
+impl<F> Positional<ChaCha12Rng, F> {
+  /// Samples with a ChaCha rng seeded from `seed`
+  pub fn from_seed(seed: u64, weight: F) -> Self {
+    Self::new(seeded_rng(seed), weight)
+  }
+}
+
+impl<const N: usize, Idx, R, F> CellSampler<N, Idx> for Positional<R, F>
+where
+  R: Rng + Clone,
+  F: Fn(&Idx, usize) -> f64,
+{
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    let Self { rng, weight } = self;
+    sample_weighted(rng, entries, |tile| Some(weight(cell.idx, tile)))
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
+  }
+}
@@ -0,0 +1,167 @@
+use super::{
+  errors::validate, seeded_rng, weighted::sample_weighted, CellSampler, SampleError, Sampler,
+  WeightError,
+};
+use crate::selection::Cell;
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Samples entries by their weights, sharpened or flattened by a temperature.
+///
+/// Each weight `w` is raised to `w ^ (1 / t)` for a temperature `t`, so that:
+/// - at `0` the entry with the highest weight is always taken
+/// - at `1` this is the same as `Weighted`
+/// - above `1` the weights are flattened towards `Uniform`
+///
+/// The temperature can change during a run by giving a schedule (i.e. to be
+/// deterministic near the centre of a map and more varied further out).<br>
+/// Without a schedule this is also a `Sampler`, as the cell isn't needed.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Temperature<R, F = ()> {
+  rng: R,
+  weights: Arc<[f64]>,
+  temperature: f64,
+  schedule: F,
+  /// The number of entries sampled so far
+  samples: usize,
+}
+
+/// Decides the temperature to sample a cell at, from the cell's index and the
+/// number of entries sampled so far (including any that were rejected)
+pub trait Schedule<Idx> {
+  fn temperature(&self, base: f64, idx: &Idx, samples: usize) -> f64;
+}
+
+/// Keeps the temperature the same throughout a run
+impl<Idx> Schedule<Idx> for () {
+  fn temperature(&self, base: f64, _idx: &Idx, _samples: usize) -> f64 {
+    base
+  }
+}
+
+impl<Idx, F: Fn(&Idx, usize) -> f64> Schedule<Idx> for F {
+  fn temperature(&self, _base: f64, idx: &Idx, samples: usize) -> f64 {
+    self(idx, samples)
+  }
+}
+
+impl<R: Rng> Temperature<R> {
+  /// Creates a sampler from the weight of each entry, which must all be finite
+  /// and non-negative with at least one positive weight
+  pub fn new(
+    rng: R,
+    weights: impl Into<Arc<[f64]>>,
+    temperature: f64,
+  ) -> Result<Self, WeightError> {
+    let weights = weights.into();
+    validate(&weights)?;
+    Ok(Self {
+      rng,
+      weights,
+      temperature,
+      schedule: (),
+      samples: 0,
+    })
+  }
+}
+
+impl Temperature<ChaCha12Rng> {
+  /// Samples with a ChaCha rng seeded from `seed`
+  pub fn from_seed(
+    seed: u64,
+    weights: impl Into<Arc<[f64]>>,
+    temperature: f64,
+  ) -> Result<Self, WeightError> {
+    Self::new(seeded_rng(seed), weights, temperature)
+  }
+}
+
+impl<R, F> Temperature<R, F> {
+  /// Changes the temperature during a run, replacing the fixed temperature
+  pub fn with_schedule<F1>(self, schedule: F1) -> Temperature<R, F1> {
+    Temperature {
+      rng: self.rng,
+      weights: self.weights,
+      temperature: self.temperature,
+      schedule,
+      samples: self.samples,
+    }
+  }
+
+  /// The weight of each entry
+  pub fn weights(&self) -> &Arc<[f64]> {
+    &self.weights
+  }
+
+  /// The temperature used when there's no schedule
+  pub fn temperature(&self) -> f64 {
+    self.temperature
+  }
+}
+
+impl<R: Rng, F> Temperature<R, F> {
+  /// Samples an entry at the given temperature
+  fn sample_at(&mut self, temperature: f64, entries: &[usize]) -> Result<usize, SampleError> {
+    self.samples += 1;
+
+    if entries.is_empty() {
+      return Err(SampleError::Empty);
+    }
+    let mut max = None;
+    for &tile in entries {
+      let weight = *self
+        .weights
+        .get(tile)
+        .ok_or(SampleError::MissingWeight(tile))?;
+      if weight > 0.0 && max.is_none_or(|(_, max)| weight > max) {
+        max = Some((tile, weight));
+      }
+    }
+    let (argmax, max) = max.ok_or(SampleError::ZeroWeight)?;
+
+    // @note a temperature that isn't positive (or is NaN) is treated as `0`
+    if temperature.is_nan() || temperature <= 0.0 {
+      return Ok(argmax);
+    }
+
+    // @note weights are scaled by the highest weight, so that low temperatures
+    // can't overflow
+    let Self { rng, weights, .. } = self;
+    sample_weighted(rng, entries, |tile| {
+      let weight = weights[tile];
+      Some(match weight > 0.0 {
+        true => ((weight.ln() - max.ln()) / temperature).exp(),
+        false => 0.0,
+      })
+    })
+  }
+}
+
+impl<R: Rng> Sampler for Temperature<R> {
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError> {
+    self.sample_at(self.temperature, entries)
+  }
+}
+
+impl<const N: usize, Idx, R, F> CellSampler<N, Idx> for Temperature<R, F>
+where
+  R: Rng + Clone,
+  F: Schedule<Idx>,
+{
+  fn sample_cell(
+    &mut self,
+    cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    let temperature = (self.schedule).temperature(self.temperature, cell.idx, self.samples);
+    self.sample_at(temperature, entries)
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
+  }
+}
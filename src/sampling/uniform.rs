@@ -1,18 +1,49 @@
-use super::Sampler;
+use super::{seeded_rng, CellSampler, SampleError, Sampler};
+use crate::selection::Cell;
 use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Randomly takes any entry in the collection with equal likelihood
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Uniform<R: Rng> {
   rng: R,
 }
 
+impl<R: Rng> Uniform<R> {
+  pub fn new(rng: R) -> Self {
+    Self { rng }
+  }
+}
+
+impl Uniform<ChaCha12Rng> {
+  /// Samples with a ChaCha rng seeded from `seed`
+  pub fn from_seed(seed: u64) -> Self {
+    Self::new(seeded_rng(seed))
+  }
+}
+
 impl<R: Rng> Sampler for Uniform<R> {
-  fn sample(&mut self, entries: &[usize]) -> usize {
-    assert!(
-      !entries.is_empty(),
-      "Should sample from a non-empty collection"
-    );
-    entries[self.rng.gen_range(0..entries.len())]
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError> {
+    if entries.is_empty() {
+      return Err(SampleError::Empty);
+    }
+    Ok(entries[self.rng.gen_range(0..entries.len())])
+  }
+}
+
+impl<const N: usize, Idx, R: Rng + Clone> CellSampler<N, Idx> for Uniform<R> {
+  fn sample_cell(
+    &mut self,
+    _cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    self.sample(entries)
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
   }
 }
@@ -1,5 +1,10 @@
-use super::Sampler;
+use super::{errors::validate, seeded_rng, CellSampler, SampleError, Sampler, WeightError};
+use crate::selection::Cell;
 use rand::{distributions::WeightedIndex, Rng};
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Uses each entry to fetch the probability at which it should be returned
 ///
@@ -12,32 +17,134 @@ use rand::{distributions::WeightedIndex, Rng};
 /// - `3` with probability `0.4`
 /// - `1` with probability `0.2`
 /// - `0` with probability `0.1`
+///
+/// The weights are shared between clones of the sampler, so cloning it into
+/// each new state is cheap. Updating the weights of one sampler copies them
+/// first, leaving any other samplers (i.e. those in earlier states) untouched.
 #[derive(Clone, Debug, Default)]
-pub struct Weighted<'a, R> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Weighted<R> {
   rng: R,
-  weights: &'a [f64],
+  weights: Arc<[f64]>,
+}
+
+impl<R: Rng> Weighted<R> {
+  /// Creates a sampler from the weight of each entry, which must all be finite
+  /// and non-negative with at least one positive weight
+  pub fn new(rng: R, weights: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    let weights = weights.into();
+    validate(&weights)?;
+    Ok(Self { rng, weights })
+  }
+
+  /// Creates a sampler from the weights of named tiles, where `names` gives
+  /// the name of each tile in the order the tiles were given to the constraint.
+  ///
+  /// Any tile that isn't given a weight has a weight of `0`, so is never
+  /// picked, and sampling fails with [`SampleError::ZeroWeight`] if only such
+  /// tiles are left.
+  pub fn from_names<S: AsRef<str>>(
+    rng: R,
+    names: &[S],
+    weights: impl IntoIterator<Item = (S, f64)>,
+  ) -> Result<Self, WeightError> {
+    let mut table = vec![0.0; names.len()];
+    for (name, weight) in weights {
+      let tile = names
+        .iter()
+        .position(|n| n.as_ref() == name.as_ref())
+        .ok_or_else(|| WeightError::UnknownName(name.as_ref().to_string()))?;
+      table[tile] = weight;
+    }
+    Self::new(rng, table)
+  }
+}
+
+impl Weighted<ChaCha12Rng> {
+  /// Samples with a ChaCha rng seeded from `seed`
+  pub fn from_seed(seed: u64, weights: impl Into<Arc<[f64]>>) -> Result<Self, WeightError> {
+    Self::new(seeded_rng(seed), weights)
+  }
+}
+
+impl<R> Weighted<R> {
+  /// The weight of each entry, which can be cheaply shared (i.e. with the
+  /// `Entropy` selector)
+  pub fn weights(&self) -> &Arc<[f64]> {
+    &self.weights
+  }
+
+  /// Replaces the weight of every entry
+  pub fn set_weights(&mut self, weights: impl Into<Arc<[f64]>>) -> Result<(), WeightError> {
+    let weights = weights.into();
+    validate(&weights)?;
+    self.weights = weights;
+    Ok(())
+  }
+
+  /// Changes the weight of a single tile.
+  ///
+  /// @note tiles past the end of the weights are added, with any tiles in
+  /// between given a weight of `0`
+  pub fn set_weight(&mut self, tile: usize, weight: f64) -> Result<(), WeightError> {
+    let mut weights = self.weights.to_vec();
+    if tile >= weights.len() {
+      weights.resize(tile + 1, 0.0);
+    }
+    weights[tile] = weight;
+    self.set_weights(weights)
+  }
 }
 
-impl<'a, R0, R1> PartialEq<Weighted<'a, R1>> for Weighted<'a, R0> {
-  fn eq(&self, other: &Weighted<'a, R1>) -> bool {
+impl<R0, R1> PartialEq<Weighted<R1>> for Weighted<R0> {
+  fn eq(&self, other: &Weighted<R1>) -> bool {
     self.weights == other.weights
   }
 }
-impl<'a, R> Eq for Weighted<'a, R> {}
+impl<R> Eq for Weighted<R> {}
 
-impl<'a, R: Rng> Sampler for Weighted<'a, R> {
-  fn sample(&mut self, entries: &[usize]) -> usize {
-    assert!(
-      !entries.is_empty(),
-      "Should sample from a non-empty collection"
-    );
-    assert!(
-      &self.weights.len() > entries.iter().max().unwrap_or(&0),
-      "The #of weights should be greater than the maximum item"
-    );
+impl<R: Rng> Sampler for Weighted<R> {
+  fn sample(&mut self, entries: &[usize]) -> Result<usize, SampleError> {
+    sample_weighted(&mut self.rng, entries, |tile| {
+      self.weights.get(tile).copied()
+    })
+  }
+}
+
+impl<const N: usize, Idx, R: Rng + Clone> CellSampler<N, Idx> for Weighted<R> {
+  fn sample_cell(
+    &mut self,
+    _cell: &Cell<'_, N, Idx>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    self.sample(entries)
+  }
 
-    let weights: Vec<_> = entries.iter().map(|&i| self.weights[i]).collect();
-    let dist = WeightedIndex::new(weights).unwrap();
-    entries[self.rng.sample(dist)]
+  fn restarted(&mut self, last: &Self) {
+    self.rng.clone_from(&last.rng);
   }
 }
+
+/// Samples an entry with probability proportional to its weight, given by a
+/// function that returns `None` if an entry has no weight
+pub(super) fn sample_weighted(
+  rng: &mut impl Rng,
+  entries: &[usize],
+  mut weight: impl FnMut(usize) -> Option<f64>,
+) -> Result<usize, SampleError> {
+  if entries.is_empty() {
+    return Err(SampleError::Empty);
+  }
+
+  let mut weights = Vec::with_capacity(entries.len());
+  for &tile in entries {
+    let weight = weight(tile).ok_or(SampleError::MissingWeight(tile))?;
+    if !weight.is_finite() || weight < 0.0 {
+      return Err(SampleError::InvalidWeight(tile));
+    }
+    weights.push(weight);
+  }
+
+  let dist = WeightedIndex::new(weights).map_err(|_| SampleError::ZeroWeight)?;
+  Ok(entries[rng.sample(dist)])
+}
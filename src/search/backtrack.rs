@@ -1,4 +1,9 @@
-use super::{Search, State};
+#[cfg(feature = "serde")]
+use super::Checkpoint;
+use super::{Search, SearchStats, State, Stepwise};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{task::Poll, time::Instant};
 
 /// Performs a Depth First Search of possible states.
 ///
@@ -20,25 +25,56 @@ use super::{Search, State};
 /// 1. @state_2, goal reached
 /// 1. output Ok(state_2), backtrack to state_0
 /// 1. no actions left, backtrack and finish
+///
+/// Whenever a state is backtracked out of without reaching a goal, it's
+/// checked against the deepest such state so far to find the best partial.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Backtrack<S: State> {
-  history: Vec<(S, Vec<S::Action>)>,
+  /// Each state being searched, alongside the actions left to take from it
+  /// and whether a goal has been reached from it yet
+  history: Vec<(S, Vec<S::Action>, bool)>,
+  best: Option<(usize, S)>,
+  stats: SearchStats,
 }
 
-impl<S: State> Iterator for Backtrack<S>
+impl<S: State> Backtrack<S> {
+  /// Backtracks out of a state, keeping it if no goal was reached from it and
+  /// it's deeper than any previous dead end
+  fn record(&mut self, state: S, found: bool) {
+    self.stats.backtracks += 1;
+    if let Some((_, _, parent)) = self.history.last_mut() {
+      *parent |= found;
+    }
+
+    let depth = self.history.len();
+    if !found && self.best.as_ref().is_none_or(|(best, _)| depth > *best) {
+      self.best = Some((depth, state));
+    }
+  }
+}
+
+impl<S: State> Backtrack<S>
 where
   S::Action: Eq,
 {
-  type Item = Result<S, S::Error>;
-  fn next(&mut self) -> Option<Self::Item> {
-    let (mut state, mut actns) = self.history.pop()?;
+  fn step(&mut self) -> Poll<Option<Result<S, S::Error>>> {
+    let Some((mut state, mut actns, found)) = self.history.pop() else {
+      return Poll::Ready(None);
+    };
     if actns.is_empty() {
-      return self.next();
+      self.record(state, found);
+      if let Some((state, _, _)) = self.history.last() {
+        state.backtracked(self.history.len() - 1);
+      }
+      return Poll::Pending;
     }
 
     // get the action to take
     let choice = match state.pick_action(actns.iter()) {
-      Err(e) => return Some(Err(e.into())),
+      Err(e) => {
+        self.record(state, found);
+        return Poll::Ready(Some(Err(e.into())));
+      }
       Ok(choice) => choice,
     };
     let i = actns.iter().position(|actn| actn == &choice).unwrap();
@@ -46,16 +82,54 @@ where
 
     // get the new state for this action
     let result = state.take_action(&actn);
-    self.history.push((state, actns));
+    self.stats.record(&state, &result, self.history.len() + 1);
     let new_state = match result {
-      Err(e) => return Some(Err(e.into())),
+      Err(e) => {
+        self.history.push((state, actns, found));
+        return Poll::Ready(Some(Err(e.into())));
+      }
       Ok(new_state) => new_state,
     };
+    let goal = new_state.is_goal();
+    self.history.push((state, actns, found || goal));
+    if goal {
+      return Poll::Ready(Some(Ok(new_state)));
+    }
 
     // get the new actions for this state
     let new_actns = new_state.get_actions().into_iter().collect();
-    self.history.push((new_state, new_actns));
-    self.next()
+    self.history.push((new_state, new_actns, false));
+    Poll::Pending
+  }
+}
+
+impl<S: State> Stepwise for Backtrack<S>
+where
+  S::Action: Eq,
+{
+  fn advance(&mut self) -> Poll<Option<Self::Item>> {
+    let start = Instant::now();
+    let result = self.step();
+    self.stats.elapsed += start.elapsed();
+    result
+  }
+
+  fn decisions(&self) -> usize {
+    self.stats.decisions
+  }
+}
+
+impl<S: State> Iterator for Backtrack<S>
+where
+  S::Action: Eq,
+{
+  type Item = Result<S, S::Error>;
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Poll::Ready(item) = self.advance() {
+        return item;
+      }
+    }
   }
 }
 
@@ -66,7 +140,69 @@ where
   fn new(start: S) -> Self {
     let actns = start.get_actions().into_iter().collect();
     Self {
-      history: vec![(start, actns)],
+      history: vec![(start, actns, false)],
+      best: None,
+      stats: SearchStats::default(),
+    }
+  }
+
+  fn best_partial(&self) -> Option<&S> {
+    self.best.as_ref().map(|(_, state)| state)
+  }
+
+  fn stats(&self) -> SearchStats {
+    self.stats
+  }
+}
+
+/// A saved form of a [`Backtrack`] search
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+pub struct SavedBacktrack<T, A> {
+  history: Vec<(T, Vec<A>, bool)>,
+  best: Option<(usize, T)>,
+  stats: SearchStats,
+}
+
+#[cfg(feature = "serde")]
+impl<S: State + Checkpoint> Checkpoint for Backtrack<S>
+where
+  S::Action: Clone + Serialize + DeserializeOwned,
+{
+  type Saved = SavedBacktrack<S::Saved, S::Action>;
+  type Context = S::Context;
+
+  fn save(&self) -> Self::Saved {
+    SavedBacktrack {
+      history: (self.history.iter())
+        .map(|(state, actns, found)| (state.save(), actns.clone(), *found))
+        .collect(),
+      best: self
+        .best
+        .as_ref()
+        .map(|(depth, best)| (*depth, best.save())),
+      stats: self.stats,
+    }
+  }
+
+  fn restore(saved: Self::Saved, context: Self::Context) -> Self {
+    let mut search = Self {
+      history: (saved.history.into_iter())
+        .map(|(state, actns, found)| (S::restore(state, context), actns, found))
+        .collect(),
+      best: saved
+        .best
+        .map(|(depth, best)| (depth, S::restore(best, context))),
+      stats: saved.stats,
+    };
+    if let Some(((first, _, _), rest)) = search.history.split_first_mut() {
+      rest
+        .iter_mut()
+        .for_each(|(state, _, _)| state.relink(first));
+      if let Some((_, best)) = search.best.as_mut() {
+        best.relink(first);
+      }
     }
+    search
   }
 }
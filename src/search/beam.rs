@@ -0,0 +1,100 @@
+use super::{scored::Ranked, Scored, Search, SearchStats};
+use std::{collections::VecDeque, time::Instant};
+
+/// The number of states kept in the frontier by default
+const DEFAULT_WIDTH: usize = 8;
+
+/// Performs a Beam Search of possible states.
+///
+/// The search proceeds a level at a time, taking every action available to
+/// each state in the frontier (see [`State::allows`](super::State::allows)).
+/// Only the best `width` non-goal states are kept for the next level, the rest
+/// are discarded.
+///
+/// Goal states are output (best first) as soon as their level is expanded,
+/// alongside any errors found whilst expanding that level.
+pub struct Beam<S: Scored> {
+  width: usize,
+  frontier: Vec<S>,
+  results: VecDeque<Result<S, S::Error>>,
+  depth: usize,
+  stats: SearchStats,
+}
+
+impl<S: Scored> Beam<S> {
+  /// Creates a search that keeps the best `width` states at each level
+  pub fn with_width(start: S, width: usize) -> Self {
+    let mut search = Self {
+      width,
+      frontier: vec![],
+      results: VecDeque::new(),
+      depth: 0,
+      stats: SearchStats::default(),
+    };
+    match start.is_goal() {
+      true => search.results.push_back(Ok(start)),
+      false => search.frontier.push(start),
+    }
+    search
+  }
+
+  /// Expands the current frontier into the next level
+  fn expand(&mut self) {
+    self.depth += 1;
+    let mut goals = vec![];
+    let mut states = vec![];
+    for state in std::mem::take(&mut self.frontier) {
+      for actn in state.get_actions() {
+        if !state.allows(&actn) {
+          continue;
+        }
+        let result = state.take_action(&actn);
+        self.stats.record(&state, &result, self.depth);
+        match result {
+          Err(e) => self.results.push_back(Err(e.into())),
+          Ok(new_state) if new_state.is_goal() => {
+            goals.push(Ranked::new(new_state, goals.len(), self.depth))
+          }
+          Ok(new_state) => states.push(Ranked::new(new_state, states.len(), self.depth)),
+        }
+      }
+    }
+
+    goals.sort_by(|a, b| b.cmp(a));
+    self
+      .results
+      .extend(goals.into_iter().map(|ranked| Ok(ranked.state)));
+
+    states.sort_by(|a, b| b.cmp(a));
+    states.truncate(self.width);
+    self.frontier = states.into_iter().map(|ranked| ranked.state).collect();
+  }
+}
+
+impl<S: Scored> Iterator for Beam<S> {
+  type Item = Result<S, S::Error>;
+  fn next(&mut self) -> Option<Self::Item> {
+    let start = Instant::now();
+    let result = loop {
+      if let Some(result) = self.results.pop_front() {
+        break Some(result);
+      }
+      if self.frontier.is_empty() {
+        break None;
+      }
+      self.expand();
+    };
+    self.stats.elapsed += start.elapsed();
+    result
+  }
+}
+
+impl<S: Scored> Search<S> for Beam<S> {
+  fn new(start: S) -> Self {
+    Self::with_width(start, DEFAULT_WIDTH)
+  }
+
+  fn stats(&self) -> SearchStats {
+    self.stats
+  }
+}
@@ -0,0 +1,81 @@
+use super::{scored::Ranked, Scored, Search, SearchStats};
+use std::{
+  collections::{BinaryHeap, VecDeque},
+  time::Instant,
+};
+
+/// Performs a Best First Search of possible states.
+///
+/// All states found so far are kept in a frontier, ranked by their score.
+/// The best state in the frontier is expanded by taking every action
+/// available to it (see [`State::allows`](super::State::allows)), with goal
+/// states only output once they're the best state in the frontier. Any errors
+/// found whilst expanding a state are output before the search continues.
+///
+/// As the whole frontier is kept in memory, this is best suited to objectives
+/// that strongly separate good and bad partial states.
+pub struct BestFirst<S: Scored> {
+  frontier: BinaryHeap<Ranked<S>>,
+  errors: VecDeque<S::Error>,
+  ranked: usize,
+  stats: SearchStats,
+}
+
+impl<S: Scored> BestFirst<S> {
+  fn push(&mut self, state: S, depth: usize) {
+    self.frontier.push(Ranked::new(state, self.ranked, depth));
+    self.ranked += 1;
+  }
+
+  fn step(&mut self) -> Option<Result<S, S::Error>> {
+    loop {
+      if let Some(e) = self.errors.pop_front() {
+        return Some(Err(e));
+      }
+
+      let Ranked { state, depth, .. } = self.frontier.pop()?;
+      if state.is_goal() {
+        return Some(Ok(state));
+      }
+
+      for actn in state.get_actions() {
+        if !state.allows(&actn) {
+          continue;
+        }
+        let result = state.take_action(&actn);
+        self.stats.record(&state, &result, depth + 1);
+        match result {
+          Err(e) => self.errors.push_back(e.into()),
+          Ok(new_state) => self.push(new_state, depth + 1),
+        }
+      }
+    }
+  }
+}
+
+impl<S: Scored> Iterator for BestFirst<S> {
+  type Item = Result<S, S::Error>;
+  fn next(&mut self) -> Option<Self::Item> {
+    let start = Instant::now();
+    let result = self.step();
+    self.stats.elapsed += start.elapsed();
+    result
+  }
+}
+
+impl<S: Scored> Search<S> for BestFirst<S> {
+  fn new(start: S) -> Self {
+    let mut search = Self {
+      frontier: BinaryHeap::new(),
+      errors: VecDeque::new(),
+      ranked: 0,
+      stats: SearchStats::default(),
+    };
+    search.push(start, 0);
+    search
+  }
+
+  fn stats(&self) -> SearchStats {
+    self.stats
+  }
+}
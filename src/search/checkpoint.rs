@@ -0,0 +1,155 @@
+use super::Stepwise;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+  fmt::Display,
+  fs::{rename, File},
+  io::{BufReader, BufWriter, Write},
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  task::Poll,
+};
+
+/// An error produced whilst writing or reading a checkpoint file
+#[derive(Debug)]
+pub enum CheckpointError {
+  Io(std::io::Error),
+  Encoding(bincode::Error),
+}
+
+impl Display for CheckpointError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Io(e) => write!(f, "Failed to access checkpoint: {e}"),
+      Self::Encoding(e) => write!(f, "Failed to encode checkpoint: {e}"),
+    }
+  }
+}
+
+impl From<std::io::Error> for CheckpointError {
+  fn from(value: std::io::Error) -> Self {
+    Self::Io(value)
+  }
+}
+
+impl From<bincode::Error> for CheckpointError {
+  fn from(value: bincode::Error) -> Self {
+    Self::Encoding(value)
+  }
+}
+
+/// A state or search that can be saved to, and restored from, a checkpoint.
+///
+/// Anything borrowed (i.e. the grid and constraint of a `WFCState`) isn't
+/// saved, and has to be provided again as context when restoring. Everything
+/// else, including the state of any random number generators, is saved so
+/// that a restored search will continue exactly as the original would have.
+pub trait Checkpoint: Sized {
+  /// An owned form of this that can be serialised
+  type Saved: Serialize + DeserializeOwned;
+  /// Anything needed to restore this that isn't saved
+  type Context: Copy;
+
+  fn save(&self) -> Self::Saved;
+  fn restore(saved: Self::Saved, context: Self::Context) -> Self;
+
+  /// Shares anything with `other` that was shared between them before they
+  /// were saved, as each is restored with its own copy (i.e. the weights learnt
+  /// by a [`DomWdeg`](crate::selection::DomWdeg) selector)
+  fn relink(&mut self, _other: &Self) {}
+
+  /// Writes a checkpoint to the file at `path`, replacing it if it exists.
+  ///
+  /// The checkpoint is written to a temporary file alongside `path` first,
+  /// and only moved over `path` once it's complete, so a failed save will
+  /// leave the last checkpoint intact.
+  fn save_to(&self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+    let path = path.as_ref();
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+
+    let mut file = BufWriter::new(File::create(&temp)?);
+    bincode::serialize_into(&mut file, &self.save())?;
+    file.flush()?;
+    file.get_ref().sync_all()?;
+    drop(file);
+    Ok(rename(temp, path)?)
+  }
+
+  /// Restores from the checkpoint saved in the file at `path`
+  fn restore_from(path: impl AsRef<Path>, context: Self::Context) -> Result<Self, CheckpointError> {
+    let file = BufReader::new(File::open(path)?);
+    Ok(Self::restore(bincode::deserialize_from(file)?, context))
+  }
+
+  /// Saves a checkpoint to the file at `path` after every `period` actions
+  /// taken, including part-way through generating an item
+  fn checkpoint_every(self, period: usize, path: impl Into<PathBuf>) -> Periodic<Self>
+  where
+    Self: Stepwise,
+  {
+    Periodic {
+      saved: self.decisions(),
+      search: self,
+      path: path.into(),
+      period,
+      requested: Arc::default(),
+      error: None,
+    }
+  }
+}
+
+/// Periodically saves a checkpoint of a search whilst it's iterated, counting
+/// the actions the search takes rather than the items it produces.
+///
+/// Failing to save a checkpoint won't stop the search, but the latest error
+/// can be checked via [`Periodic::error`].
+pub struct Periodic<T> {
+  search: T,
+  path: PathBuf,
+  period: usize,
+  /// The number of actions taken when the last checkpoint was saved
+  saved: usize,
+  requested: Arc<AtomicBool>,
+  error: Option<CheckpointError>,
+}
+
+impl<T> Periodic<T> {
+  /// The error produced by the last failed attempt to save a checkpoint
+  pub fn error(&self) -> Option<&CheckpointError> {
+    self.error.as_ref()
+  }
+
+  /// A flag that, once set, saves a checkpoint after the next step of the
+  /// search (i.e. from another thread whilst the search is being iterated)
+  pub fn save_requests(&self) -> Arc<AtomicBool> {
+    self.requested.clone()
+  }
+
+  pub fn into_inner(self) -> T {
+    self.search
+  }
+}
+
+impl<T: Checkpoint + Stepwise> Iterator for Periodic<T> {
+  type Item = T::Item;
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let item = self.search.advance();
+      let decisions = self.search.decisions();
+      let due = self.period > 0 && decisions >= self.saved + self.period;
+      if self.requested.swap(false, Ordering::Relaxed) || due {
+        self.saved = decisions;
+        if let Err(e) = self.search.save_to(&self.path) {
+          self.error = Some(e);
+        }
+      }
+
+      if let Poll::Ready(item) = item {
+        return item;
+      }
+    }
+  }
+}
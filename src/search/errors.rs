@@ -1,8 +1,9 @@
-use crate::consistency::AC3Error;
+use crate::{consistency::AC3Error, sampling::SampleError};
+use std::fmt::Display;
 
 pub enum WFCError<Idx> {
   GetActionError,
-  PickActionError(String),
+  PickActionError(PickActionError),
   TakeActionError(AC3Error<Idx>),
 }
 
@@ -11,3 +12,39 @@ impl<Idx> From<AC3Error<Idx>> for WFCError<Idx> {
     Self::TakeActionError(value)
   }
 }
+
+impl<Idx> From<PickActionError> for WFCError<Idx> {
+  fn from(value: PickActionError) -> Self {
+    Self::PickActionError(value)
+  }
+}
+
+/// An error produced when a tile can't be picked for a cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PickActionError {
+  /// There were no actions to pick from
+  NoActions,
+  /// The cell to collapse doesn't exist
+  MissingCell,
+  /// The observer vetoed every action
+  Vetoed,
+  /// The sampler couldn't sample any of the tiles
+  Sample(SampleError),
+}
+
+impl Display for PickActionError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::NoActions => write!(f, "No actions available"),
+      Self::MissingCell => write!(f, "The cell to collapse doesn't exist"),
+      Self::Vetoed => write!(f, "Every action was vetoed"),
+      Self::Sample(e) => write!(f, "Failed to sample a tile: {e}"),
+    }
+  }
+}
+
+impl From<SampleError> for PickActionError {
+  fn from(value: SampleError) -> Self {
+    Self::Sample(value)
+  }
+}
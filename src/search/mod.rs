@@ -1,13 +1,37 @@
+#[cfg(feature = "serde")]
+mod checkpoint;
+#[cfg(feature = "serde")]
+pub use checkpoint::{Checkpoint, CheckpointError, Periodic};
+#[cfg(feature = "serde")]
+pub use {backtrack::SavedBacktrack, naive::SavedNaive, restart::SavedRestart, state::SavedState};
 mod backtrack;
 pub use backtrack::Backtrack;
+mod beam;
+pub use beam::Beam;
+mod best_first;
+pub use best_first::BestFirst;
 mod naive;
 pub use naive::Naive;
+mod par_backtrack;
+pub use par_backtrack::ParBacktrack;
+mod observer;
+pub use observer::WfcObserver;
 mod restart;
 pub use restart::Restart;
+mod scored;
+pub use scored::{ScoreBy, Scored};
+mod solutions;
+pub use solutions::{count_solutions, AllSolutions, Canonical, SolutionCount};
 mod state;
+mod steps;
 pub use state::WFCState;
+pub use steps::{Evented, Steps, WFCEvent};
 mod errors;
-pub use errors::WFCError;
+pub use errors::{PickActionError, WFCError};
+mod stats;
+pub use stats::SearchStats;
+
+use std::task::Poll;
 
 /// A generic implementation of state for search methods.
 ///
@@ -40,12 +64,37 @@ pub trait State: Sized {
     actions: impl IntoIterator<Item = &'a Self::Action>,
   ) -> Result<Self::Action, Self::PickError>;
 
+  /// Whether an action may be taken, for searches that take every action
+  /// rather than picking one (i.e. `BestFirst`, `Beam` and the levels that
+  /// `ParBacktrack` expands eagerly).
+  ///
+  /// These searches never call `pick_action`, so actions are only checked
+  /// against this (i.e. so that observers can still veto actions).
+  fn allows(&self, _action: &Self::Action) -> bool {
+    true
+  }
+
   /// The error produced whilst attempting to transition to a new state
   type TakeError: Into<Self::Error>;
   /// Takes an action and transitions into a new state<br>
   /// This should produce a new state from a reference to this state (i.e. via
   /// `clone`)
   fn take_action(&self, action: &Self::Action) -> Result<Self, Self::TakeError>;
+
+  /// The total number of propagation tasks run to reach this state<br>
+  /// States that don't propagate constraints can leave this as `0`
+  fn propagation_tasks(&self) -> usize {
+    0
+  }
+
+  /// Called by searches when they return to this state after abandoning the
+  /// states that followed it, with the number of actions taken to reach it
+  fn backtracked(&self, _depth: usize) {}
+
+  /// Called by searches on the state they restart from, with the state the
+  /// last attempt ended in, i.e. to carry random number generators forward so
+  /// that attempts don't repeat each other
+  fn restarted(&mut self, _last: &Self) {}
 }
 
 pub trait Search<S: State>: Iterator<Item = Result<S, S::Error>> + Sized {
@@ -53,4 +102,26 @@ pub trait Search<S: State>: Iterator<Item = Result<S, S::Error>> + Sized {
   fn next_valid(&mut self) -> Option<S> {
     self.find_map(|item| item.ok())
   }
+
+  /// The deepest partial state reached by a failed attempt so far, i.e. the
+  /// one that had the most actions taken before it could go no further.<br>
+  /// Searches that don't keep track of this will always return `None`.
+  fn best_partial(&self) -> Option<&S> {
+    None
+  }
+
+  /// Statistics on the work the search has done so far
+  fn stats(&self) -> SearchStats;
+}
+
+/// A search that can be advanced one step at a time, where each step takes at
+/// most one action, such that it can be checkpointed part-way through
+/// generating an item.
+pub trait Stepwise: Iterator {
+  /// Takes the next step, returning `Ready` with the next item (or `None` once
+  /// the search is finished) if the step produced one, otherwise `Pending`
+  fn advance(&mut self) -> Poll<Option<Self::Item>>;
+
+  /// The number of actions taken so far
+  fn decisions(&self) -> usize;
 }
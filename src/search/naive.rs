@@ -1,35 +1,133 @@
-use super::{Search, State};
+#[cfg(feature = "serde")]
+use super::Checkpoint;
+use super::{Search, SearchStats, State, Stepwise};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{task::Poll, time::Instant};
 
 /// Generates a single failure/success and then None repeatedly
+///
+/// On failure, the state we were unable to progress from is kept as the best
+/// partial state.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Naive<S> {
   item: Option<S>,
+  depth: usize,
+  best: Option<S>,
+  stats: SearchStats,
+}
+
+impl<S: State> Naive<S> {
+  fn step(&mut self) -> Poll<Option<Result<S, S::Error>>> {
+    let Some(mut state) = self.item.take() else {
+      return Poll::Ready(None);
+    };
+    if state.is_goal() {
+      return Poll::Ready(Some(Ok(state)));
+    }
+
+    let actns: Vec<_> = state.get_actions().into_iter().collect();
+    let choice = match state.pick_action(actns.iter()) {
+      Err(e) => {
+        self.best = Some(state);
+        return Poll::Ready(Some(Err(e.into())));
+      }
+      Ok(choice) => choice,
+    };
+
+    self.depth += 1;
+    let result = state.take_action(&choice);
+    self.stats.record(&state, &result, self.depth);
+    match result {
+      Err(e) => {
+        self.best = Some(state);
+        Poll::Ready(Some(Err(e.into())))
+      }
+      Ok(state) => {
+        self.item = Some(state);
+        Poll::Pending
+      }
+    }
+  }
+}
+
+impl<S: State> Stepwise for Naive<S> {
+  fn advance(&mut self) -> Poll<Option<Self::Item>> {
+    let start = Instant::now();
+    let result = self.step();
+    self.stats.elapsed += start.elapsed();
+    result
+  }
+
+  fn decisions(&self) -> usize {
+    self.stats.decisions
+  }
 }
 
 impl<S: State> Iterator for Naive<S> {
   type Item = Result<S, S::Error>;
   fn next(&mut self) -> Option<Self::Item> {
-    let mut state = self.item.take()?;
-
-    while !state.is_goal() {
-      let actns: Vec<_> = state.get_actions().into_iter().collect();
-      let choice = match state.pick_action(actns.iter()) {
-        Err(e) => return Some(Err(e.into())),
-        Ok(choice) => choice,
-      };
-
-      state = match state.take_action(&choice) {
-        Err(e) => return Some(Err(e.into())),
-        Ok(state) => state,
-      };
+    loop {
+      if let Poll::Ready(item) = self.advance() {
+        return item;
+      }
     }
-
-    Some(Ok(state))
   }
 }
 
 impl<S: State> Search<S> for Naive<S> {
   fn new(start: S) -> Self {
-    Self { item: Some(start) }
+    Self {
+      item: Some(start),
+      depth: 0,
+      best: None,
+      stats: SearchStats::default(),
+    }
+  }
+
+  fn best_partial(&self) -> Option<&S> {
+    self.best.as_ref()
+  }
+
+  fn stats(&self) -> SearchStats {
+    self.stats
+  }
+}
+
+/// A saved form of a [`Naive`] search
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+pub struct SavedNaive<T> {
+  item: Option<T>,
+  depth: usize,
+  best: Option<T>,
+  stats: SearchStats,
+}
+
+#[cfg(feature = "serde")]
+impl<S: State + Checkpoint> Checkpoint for Naive<S> {
+  type Saved = SavedNaive<S::Saved>;
+  type Context = S::Context;
+
+  fn save(&self) -> Self::Saved {
+    SavedNaive {
+      item: self.item.as_ref().map(S::save),
+      depth: self.depth,
+      best: self.best.as_ref().map(S::save),
+      stats: self.stats,
+    }
+  }
+
+  fn restore(saved: Self::Saved, context: Self::Context) -> Self {
+    let mut search = Self {
+      item: saved.item.map(|item| S::restore(item, context)),
+      depth: saved.depth,
+      best: saved.best.map(|best| S::restore(best, context)),
+      stats: saved.stats,
+    };
+    if let (Some(item), Some(best)) = (&search.item, &mut search.best) {
+      best.relink(item);
+    }
+    search
   }
 }
@@ -0,0 +1,27 @@
+use crate::consistency::{AC3Error, CSPDomains};
+
+/// Callbacks on the progress of generating a state, i.e. for logging or for
+/// checking rules that can't be expressed as a constraint.
+///
+/// Observers are cloned along with the state they're attached to, so anything
+/// they collect should be shared between clones (i.e. via an `Arc<Mutex<_>>`).
+/// Every callback does nothing by default.
+pub trait WfcObserver<const N: usize, Idx> {
+  /// Called once a tile has been picked for a cell, before it's assigned.<br>
+  /// Returning `false` vetoes the choice, so that a different tile is picked.
+  fn on_select(&self, _idx: &Idx, _tile: usize) -> bool {
+    true
+  }
+
+  /// Called once a tile has been assigned and propagated to all domains
+  fn on_propagate(&self, _idx: &Idx, _tile: usize, _domains: &CSPDomains<N, Idx>) {}
+
+  /// Called when assigning a tile leads to a contradiction
+  fn on_contradiction(&self, _error: &AC3Error<Idx>) {}
+
+  /// Called when a search returns to a state after abandoning later states,
+  /// with the number of actions taken to reach the state.
+  fn on_backtrack(&self, _depth: usize) {}
+}
+
+impl<const N: usize, Idx> WfcObserver<N, Idx> for () {}
@@ -0,0 +1,199 @@
+use super::{Backtrack, Search, SearchStats, State};
+use rayon::prelude::*;
+use std::{
+  sync::{
+    mpsc::{sync_channel, Receiver, SyncSender},
+    Arc, RwLock,
+  },
+  time::Instant,
+};
+
+/// The number of levels of the action tree split into subproblems by default
+const DEFAULT_DEPTH: usize = 2;
+
+/// The number of results that can be waiting to be received by default
+const DEFAULT_BOUND: usize = 64;
+
+/// Performs a Depth First Search of possible states across rayon workers.
+///
+/// The first `depth` levels of the action tree are expanded eagerly, taking
+/// every action available at each level. Each state left at the bottom of
+/// these levels is then explored as an independent [`Backtrack`] search on
+/// the rayon thread pool (see [`State::allows`] for how actions in the expanded
+/// levels are checked).
+///
+/// All successes/failures are sent back over a bounded channel, so they'll be
+/// yielded in whatever order the workers find them. Workers wait once the
+/// channel is full, until results are received or the search is dropped, which
+/// will stop the workers the next time they attempt to send a result.
+///
+/// As the workers run in the background, states have to be `Send + 'static`,
+/// i.e. a `WFCState` will need to borrow its grid and constraint statically.
+///
+/// Statistics are kept per subproblem (with the first entry for splitting the
+/// tree) and combined on request.
+pub struct ParBacktrack<S: State> {
+  start: Option<S>,
+  depth: usize,
+  bound: usize,
+  results: Option<Receiver<Result<S, S::Error>>>,
+  stats: Arc<RwLock<Vec<SearchStats>>>,
+}
+
+impl<S: State> ParBacktrack<S> {
+  /// Creates a search that splits the first `depth` levels of the action tree
+  /// into independent subproblems.
+  pub fn with_depth(start: S, depth: usize) -> Self {
+    Self {
+      start: Some(start),
+      depth,
+      bound: DEFAULT_BOUND,
+      results: None,
+      stats: Arc::new(RwLock::new(vec![SearchStats::default()])),
+    }
+  }
+
+  /// Sets the number of results that can be waiting to be received before
+  /// the workers wait, by default `64`
+  pub fn with_bound(self, bound: usize) -> Self {
+    Self { bound, ..self }
+  }
+}
+
+impl<S> ParBacktrack<S>
+where
+  S: State + Send + 'static,
+  S::Action: Eq,
+  S::Error: Send + 'static,
+{
+  /// Expands the first `depth` levels of the action tree, sending any goals
+  /// or errors found on the way.
+  ///
+  /// Returns the states at the bottom of the expanded levels, or `None` if the
+  /// receiving end of the channel has been dropped.
+  fn split(
+    start: S,
+    depth: usize,
+    results: &SyncSender<Result<S, S::Error>>,
+    stats: &mut SearchStats,
+  ) -> Option<Vec<S>> {
+    if start.is_goal() {
+      results.send(Ok(start)).ok()?;
+      return Some(vec![]);
+    }
+
+    let mut frontier = vec![start];
+    for level in 1..=depth {
+      let mut next_frontier = vec![];
+      for state in frontier {
+        for actn in state.get_actions() {
+          if !state.allows(&actn) {
+            continue;
+          }
+          let result = state.take_action(&actn);
+          stats.record(&state, &result, level);
+          match result {
+            Err(e) => results.send(Err(e.into())).ok()?,
+            Ok(new_state) if new_state.is_goal() => results.send(Ok(new_state)).ok()?,
+            Ok(new_state) => next_frontier.push(new_state),
+          }
+        }
+      }
+      frontier = next_frontier;
+    }
+
+    Some(frontier)
+  }
+
+  /// Starts the search on the rayon thread pool
+  fn spawn(
+    start: S,
+    depth: usize,
+    bound: usize,
+    stats: Arc<RwLock<Vec<SearchStats>>>,
+  ) -> Receiver<Result<S, S::Error>> {
+    let (sender, receiver) = sync_channel(bound);
+    rayon::spawn(move || {
+      let timer = Instant::now();
+      let mut split_stats = SearchStats::default();
+      let frontier = Self::split(start, depth, &sender, &mut split_stats);
+      split_stats.elapsed = timer.elapsed();
+
+      let Some(frontier) = frontier else {
+        return;
+      };
+      {
+        let mut all_stats = stats.write().unwrap();
+        all_stats[0] = split_stats;
+        all_stats.resize(frontier.len() + 1, SearchStats::default());
+      }
+
+      frontier
+        .into_par_iter()
+        .enumerate()
+        .for_each_with(sender, |sender, (i, state)| {
+          let mut search = Backtrack::new(state);
+          loop {
+            let result = search.next();
+            let mut sub_stats = search.stats();
+            sub_stats.peak_depth += depth;
+            stats.write().unwrap()[i + 1] = sub_stats;
+
+            let Some(result) = result else {
+              return;
+            };
+            if sender.send(result).is_err() {
+              return;
+            }
+          }
+        });
+    });
+    receiver
+  }
+}
+
+impl<S> Iterator for ParBacktrack<S>
+where
+  S: State + Send + 'static,
+  S::Action: Eq,
+  S::Error: Send + 'static,
+{
+  type Item = Result<S, S::Error>;
+  fn next(&mut self) -> Option<Self::Item> {
+    if let Some(start) = self.start.take() {
+      self.results = Some(Self::spawn(
+        start,
+        self.depth,
+        self.bound,
+        self.stats.clone(),
+      ));
+    }
+    self.results.as_ref()?.recv().ok()
+  }
+}
+
+impl<S> Search<S> for ParBacktrack<S>
+where
+  S: State + Send + 'static,
+  S::Action: Eq,
+  S::Error: Send + 'static,
+{
+  fn new(start: S) -> Self {
+    Self::with_depth(start, DEFAULT_DEPTH)
+  }
+
+  /// Combines the statistics of every subproblem, with the time taken being
+  /// the time spent splitting plus the longest time spent on a subproblem.
+  fn stats(&self) -> SearchStats {
+    let all_stats = self.stats.read().unwrap();
+    let (split, subproblems) = all_stats.split_first().unwrap();
+    let mut stats = subproblems
+      .iter()
+      .fold(SearchStats::default(), |acc, stats| acc.merge(stats));
+    stats.elapsed += split.elapsed;
+    stats.merge(&SearchStats {
+      elapsed: Default::default(),
+      ..*split
+    })
+  }
+}
@@ -1,35 +1,166 @@
-use super::{Search, State};
+#[cfg(feature = "serde")]
+use super::Checkpoint;
+use super::{Search, SearchStats, State, Stepwise};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{task::Poll, time::Instant};
 
 /// Generates multiple successes/failures by restarting from an initial state
+///
+/// Each attempt carries on from the random choices of the last attempt (see
+/// [`State::restarted`]), so that attempts don't repeat each other.
+///
+/// Across all failed attempts, the state that had the most actions taken
+/// before failing is kept as the best partial state.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Restart<S> {
   item: S,
+  /// The attempt in progress, alongside the number of actions it's taken
+  current: Option<(S, usize)>,
+  best: Option<(usize, S)>,
+  attempts: usize,
+  stats: SearchStats,
+}
+
+impl<S> Restart<S> {
+  /// Keeps a failed state if it got further than any previous attempt
+  fn record(&mut self, depth: usize, state: S) {
+    if self.best.as_ref().is_none_or(|(best, _)| depth > *best) {
+      self.best = Some((depth, state));
+    }
+  }
+}
+
+impl<S: State + Clone> Restart<S> {
+  fn step(&mut self) -> Poll<Result<S, S::Error>> {
+    let (mut state, depth) = self.current.take().unwrap_or_else(|| {
+      let state = self.item.clone();
+      if self.attempts > 0 {
+        self.stats.restarts += 1;
+        state.backtracked(0);
+      }
+      self.attempts += 1;
+      (state, 0)
+    });
+    if state.is_goal() {
+      self.item.restarted(&state);
+      return Poll::Ready(Ok(state));
+    }
+
+    let actns: Vec<_> = state.get_actions().into_iter().collect();
+    let choice = match state.pick_action(actns.iter()) {
+      Err(e) => {
+        self.item.restarted(&state);
+        self.record(depth, state);
+        return Poll::Ready(Err(e.into()));
+      }
+      Ok(choice) => choice,
+    };
+
+    let result = state.take_action(&choice);
+    self.stats.record(&state, &result, depth + 1);
+    match result {
+      Err(e) => {
+        self.item.restarted(&state);
+        self.record(depth, state);
+        Poll::Ready(Err(e.into()))
+      }
+      Ok(state) => {
+        self.current = Some((state, depth + 1));
+        Poll::Pending
+      }
+    }
+  }
+}
+
+impl<S: State + Clone> Stepwise for Restart<S> {
+  fn advance(&mut self) -> Poll<Option<Self::Item>> {
+    let start = Instant::now();
+    let result = self.step();
+    self.stats.elapsed += start.elapsed();
+    result.map(Some)
+  }
+
+  fn decisions(&self) -> usize {
+    self.stats.decisions
+  }
 }
 
 impl<S: State + Clone> Iterator for Restart<S> {
   type Item = Result<S, S::Error>;
   fn next(&mut self) -> Option<Self::Item> {
-    let mut state = self.item.clone();
-
-    while !state.is_goal() {
-      let actns: Vec<_> = state.get_actions().into_iter().collect();
-      let choice = match state.pick_action(actns.iter()) {
-        Err(e) => return Some(Err(e.into())),
-        Ok(choice) => choice,
-      };
-
-      state = match state.take_action(&choice) {
-        Err(e) => return Some(Err(e.into())),
-        Ok(state) => state,
-      };
+    loop {
+      if let Poll::Ready(item) = self.advance() {
+        return item;
+      }
     }
-
-    Some(Ok(state))
   }
 }
 
 impl<S: State + Clone> Search<S> for Restart<S> {
   fn new(start: S) -> Self {
-    Self { item: start }
+    Self {
+      item: start,
+      current: None,
+      best: None,
+      attempts: 0,
+      stats: SearchStats::default(),
+    }
+  }
+
+  fn best_partial(&self) -> Option<&S> {
+    self.best.as_ref().map(|(_, state)| state)
+  }
+
+  fn stats(&self) -> SearchStats {
+    self.stats
+  }
+}
+
+/// A saved form of a [`Restart`] search
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+pub struct SavedRestart<T> {
+  item: T,
+  current: Option<(T, usize)>,
+  best: Option<(usize, T)>,
+  attempts: usize,
+  stats: SearchStats,
+}
+
+#[cfg(feature = "serde")]
+impl<S: State + Checkpoint> Checkpoint for Restart<S> {
+  type Saved = SavedRestart<S::Saved>;
+  type Context = S::Context;
+
+  fn save(&self) -> Self::Saved {
+    SavedRestart {
+      item: self.item.save(),
+      current: (self.current.as_ref()).map(|(state, depth)| (state.save(), *depth)),
+      best: self
+        .best
+        .as_ref()
+        .map(|(depth, best)| (*depth, best.save())),
+      attempts: self.attempts,
+      stats: self.stats,
+    }
+  }
+
+  fn restore(saved: Self::Saved, context: Self::Context) -> Self {
+    let mut search = Self {
+      item: S::restore(saved.item, context),
+      current: (saved.current).map(|(state, depth)| (S::restore(state, context), depth)),
+      best: saved
+        .best
+        .map(|(depth, best)| (depth, S::restore(best, context))),
+      attempts: saved.attempts,
+      stats: saved.stats,
+    };
+    let current = search.current.as_mut().map(|(state, _)| state);
+    let best = search.best.as_mut().map(|(_, state)| state);
+    for state in current.into_iter().chain(best) {
+      state.relink(&search.item);
+    }
+    search
   }
 }
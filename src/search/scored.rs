@@ -0,0 +1,141 @@
+use super::State;
+use std::cmp::Ordering;
+
+/// A state that can be ranked against other states by some objective.
+///
+/// Higher scores are treated as better, so an objective that should be
+/// minimised (i.e. the number of dead ends) can be wrapped in `Reverse`.
+pub trait Scored: State {
+  type Score: Ord;
+  /// Scores a (potentially partial) state
+  fn score(&self) -> Self::Score;
+}
+
+/// Pairs a state with a user supplied objective, so that it can be ranked.
+///
+/// The objective is cloned into every state produced from this one, so it
+/// should be cheap to clone (i.e. a function pointer or a closure over `Rc`s).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ScoreBy<S, F> {
+  state: S,
+  objective: F,
+}
+
+impl<S, F> ScoreBy<S, F> {
+  pub fn new(state: S, objective: F) -> Self {
+    Self { state, objective }
+  }
+
+  /// The state being scored
+  pub fn state(&self) -> &S {
+    &self.state
+  }
+
+  pub fn into_inner(self) -> S {
+    self.state
+  }
+}
+
+impl<S, F, O> State for ScoreBy<S, F>
+where
+  S: State,
+  F: Fn(&S) -> O + Clone,
+{
+  type Error = S::Error;
+  type Action = S::Action;
+
+  fn is_goal(&self) -> bool {
+    self.state.is_goal()
+  }
+
+  type ActnIter = S::ActnIter;
+  fn get_actions(&self) -> Self::ActnIter {
+    self.state.get_actions()
+  }
+
+  type PickError = S::PickError;
+  fn pick_action<'a>(
+    &'a mut self,
+    actions: impl IntoIterator<Item = &'a Self::Action>,
+  ) -> Result<Self::Action, Self::PickError> {
+    self.state.pick_action(actions)
+  }
+
+  fn allows(&self, action: &Self::Action) -> bool {
+    self.state.allows(action)
+  }
+
+  type TakeError = S::TakeError;
+  fn take_action(&self, action: &Self::Action) -> Result<Self, Self::TakeError> {
+    Ok(Self {
+      state: self.state.take_action(action)?,
+      objective: self.objective.clone(),
+    })
+  }
+
+  fn propagation_tasks(&self) -> usize {
+    self.state.propagation_tasks()
+  }
+
+  fn backtracked(&self, depth: usize) {
+    self.state.backtracked(depth)
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.state.restarted(&last.state)
+  }
+}
+
+impl<S, F, O> Scored for ScoreBy<S, F>
+where
+  S: State,
+  F: Fn(&S) -> O + Clone,
+  O: Ord,
+{
+  type Score = O;
+  fn score(&self) -> Self::Score {
+    (self.objective)(&self.state)
+  }
+}
+
+/// A state alongside its score, ordered solely by its score and then by the
+/// order states were ranked in (earlier states are preferred).
+pub(crate) struct Ranked<S: Scored> {
+  pub score: S::Score,
+  pub order: usize,
+  pub depth: usize,
+  pub state: S,
+}
+
+impl<S: Scored> Ranked<S> {
+  pub fn new(state: S, order: usize, depth: usize) -> Self {
+    Self {
+      score: state.score(),
+      order,
+      depth,
+      state,
+    }
+  }
+}
+
+impl<S: Scored> PartialEq for Ranked<S> {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+impl<S: Scored> Eq for Ranked<S> {}
+
+impl<S: Scored> PartialOrd for Ranked<S> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<S: Scored> Ord for Ranked<S> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self
+      .score
+      .cmp(&other.score)
+      .then_with(|| other.order.cmp(&self.order))
+  }
+}
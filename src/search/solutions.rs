@@ -0,0 +1,83 @@
+use super::{Backtrack, Search, SearchStats, State};
+use std::{collections::HashSet, hash::Hash};
+
+/// A state whose goals can be reduced to a canonical form, such that two
+/// goals are the same solution iff their canonical forms are equal.
+pub trait Canonical: State {
+  type Key: Hash + Eq;
+  fn canonical(&self) -> Self::Key;
+}
+
+/// Performs a Depth First Search of possible states, outputting each distinct
+/// goal state only once.
+///
+/// Errors are passed through as they're found, so that they can be counted
+/// and inspected alongside the solutions.
+pub struct AllSolutions<S: Canonical> {
+  search: Backtrack<S>,
+  seen: HashSet<S::Key>,
+}
+
+impl<S: Canonical> Iterator for AllSolutions<S>
+where
+  S::Action: Eq,
+{
+  type Item = Result<S, S::Error>;
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      match self.search.next()? {
+        Ok(state) if !self.seen.insert(state.canonical()) => continue,
+        result => return Some(result),
+      }
+    }
+  }
+}
+
+impl<S: Canonical> Search<S> for AllSolutions<S>
+where
+  S::Action: Eq,
+{
+  fn new(start: S) -> Self {
+    Self {
+      search: Backtrack::new(start),
+      seen: HashSet::new(),
+    }
+  }
+
+  fn best_partial(&self) -> Option<&S> {
+    self.search.best_partial()
+  }
+
+  fn stats(&self) -> SearchStats {
+    self.search.stats()
+  }
+}
+
+/// The number of distinct solutions found for a state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SolutionCount {
+  /// Every solution was found
+  Exact(usize),
+  /// The search was stopped once it reached the limit
+  AtLeast(usize),
+}
+
+/// Counts the number of distinct solutions reachable from a state, stopping
+/// once `limit` solutions have been found.
+///
+/// For example, a limit of `2` is enough to tell whether a state has no
+/// solutions, a unique solution or many solutions.
+pub fn count_solutions<S: Canonical>(start: S, limit: usize) -> SolutionCount
+where
+  S::Action: Eq,
+{
+  let count = AllSolutions::new(start)
+    .filter_map(Result::ok)
+    .take(limit)
+    .count();
+
+  match count < limit {
+    true => SolutionCount::Exact(count),
+    false => SolutionCount::AtLeast(count),
+  }
+}
@@ -1,16 +1,24 @@
-use super::{State, WFCError};
+#[cfg(feature = "serde")]
+use super::Checkpoint;
+use super::{Canonical, Evented, PickActionError, State, WFCError, WFCEvent, WfcObserver};
 use crate::{
-  consistency::{ac3, AC3Error, CSPDomains, Constraint},
+  consistency::{ac3, propagate, AC3Error, AC3ErrorKind, CSPDomains, Constraint, Domain},
   grid::Grid,
-  sampling::Sampler,
+  sampling::{CellSampler, SampleError},
+  selection::{seeded_hash, Cell, CellQueue, CellSelector, MrvDegree, ValueOrder},
 };
-use std::hash::Hash;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashSet, hash::Hash};
 
 /// A definition of state for the wfc algorithm.
 ///
 /// Bundles together everything needed to assign a tile and propagate
 /// constraints.
-pub struct WFCState<'a, const N: usize, Idx, G, S> {
+pub struct WFCState<'a, const N: usize, Idx, G, S, C = MrvDegree, O = (), V = ()>
+where
+  C: CellSelector<N, Idx>,
+{
   /// A set of domains to assign to and constrain values within.
   domains: CSPDomains<N, Idx>,
   /// The maximum number of tiles that can be in any one domain.
@@ -21,52 +29,357 @@ pub struct WFCState<'a, const N: usize, Idx, G, S> {
   /// Picks the tile that should be assigned from a collection of tiles.
   /// This can either be deterministic or random, to allow for tile variation.
   pick_domain: S,
+  /// Narrows down, or weights, the tiles that `pick_domain` picks from.
+  order_values: V,
+  /// Picks the cell that should be assigned next from the cells left.
+  select_cell: C,
+  /// The cells left to collapse, ranked by `select_cell`.
+  queue: CellQueue<Idx, C::Rank>,
   /// A constraint on which tiles can be placed next to each other.
   constraint: &'a Constraint<N>,
+  /// The total number of propagation tasks run to reach this state.
+  propagations: usize,
+  /// The cells changed by the action that produced this state.
+  changed: HashSet<Idx>,
+  /// Callbacks on the progress of generating this state.
+  observer: O,
 }
 
 impl<'a, const N: usize, Idx, G, S> WFCState<'a, N, Idx, G, S>
 where
-  Idx: Hash + Eq,
+  Idx: Clone + Hash + Eq,
+  G: Grid<N, Idx>,
+  S: CellSampler<N, Idx>,
+{
+  /// Creates a state where the cells at each of the given indices can take
+  /// any of the tiles in the constraint.
+  pub fn new(
+    idxs: impl IntoIterator<Item = Idx>,
+    grid: &'a G,
+    constraint: &'a Constraint<N>,
+    pick_domain: S,
+  ) -> Self {
+    let domain_size = constraint.no_tiles();
+    let mut domain = Domain::constraint(constraint, domain_size);
+    pick_domain.prepare(&mut domain);
+    let mut state = Self {
+      domains: idxs.into_iter().map(|idx| (idx, domain.clone())).collect(),
+      domain_size,
+      grid,
+
+      pick_domain,
+      order_values: (),
+      select_cell: MrvDegree,
+      queue: CellQueue::default(),
+      constraint,
+      propagations: 0,
+      changed: HashSet::new(),
+      observer: (),
+    };
+    state.requeue(state.domains.keys());
+    state
+  }
+}
+
+impl<'a, const N: usize, Idx, G, S, C, O, V> WFCState<'a, N, Idx, G, S, C, O, V>
+where
+  C: CellSelector<N, Idx>,
+{
+  /// The domains of every cell in this state
+  pub fn domains(&self) -> &CSPDomains<N, Idx> {
+    &self.domains
+  }
+
+  /// The sampler used to pick tiles in this state
+  pub fn sampler(&self) -> &S {
+    &self.pick_domain
+  }
+
+  /// The selector used to pick cells in this state
+  pub fn selector(&self) -> &C {
+    &self.select_cell
+  }
+
+  /// Attaches an observer, that'll be called as this state is generated
+  pub fn with_observer<O1>(self, observer: O1) -> WFCState<'a, N, Idx, G, S, C, O1, V> {
+    WFCState {
+      domains: self.domains,
+      domain_size: self.domain_size,
+      grid: self.grid,
+
+      pick_domain: self.pick_domain,
+      order_values: self.order_values,
+      select_cell: self.select_cell,
+      queue: self.queue,
+      constraint: self.constraint,
+      propagations: self.propagations,
+      changed: self.changed,
+      observer,
+    }
+  }
+
+  /// Narrows down, or weights, the tiles that are sampled from when picking
+  /// an action (see [`LeastConstraining`](crate::selection::LeastConstraining))
+  pub fn with_value_order<V1>(self, order_values: V1) -> WFCState<'a, N, Idx, G, S, C, O, V1> {
+    WFCState {
+      domains: self.domains,
+      domain_size: self.domain_size,
+      grid: self.grid,
+
+      pick_domain: self.pick_domain,
+      order_values,
+      select_cell: self.select_cell,
+      queue: self.queue,
+      constraint: self.constraint,
+      propagations: self.propagations,
+      changed: self.changed,
+      observer: self.observer,
+    }
+  }
+}
+
+impl<'a, const N: usize, Idx, G, S, C, O, V> WFCState<'a, N, Idx, G, S, C, O, V>
+where
+  Idx: Clone + Hash + Eq,
   G: Grid<N, Idx>,
+  C: CellSelector<N, Idx>,
 {
-  /// Helper method to calculate the AC3 heuristic for a domain
-  fn ac3_heuristic(&self, idx: &Idx) -> [usize; 2] {
-    let values: Vec<_> = self.domains.read_at(idx, |d| d.iter().collect()).unwrap();
-
-    let mrv = values.len();
-    if mrv == 0 {
-      return [0, 0];
-    }
-
-    let degree = self
-      .grid
-      .neighbours(idx)
-      .into_iter()
-      .enumerate()
-      .filter_map(|(side, optn)| {
-        self.domains.read_at(&optn?, |d| {
-          d.iter()
-            .map(|tile1| {
-              values
-                .iter()
-                .filter(|&&tile0| self.constraint[(tile0, tile1, side)])
-                .count()
-            })
-            .sum::<usize>()
-        })
+  /// Replaces how the next cell to collapse is selected, by default the cell
+  /// with the fewest tiles left (see [`MrvDegree`])
+  pub fn with_selector<C1>(self, select_cell: C1) -> WFCState<'a, N, Idx, G, S, C1, O, V>
+  where
+    C1: CellSelector<N, Idx>,
+  {
+    for idx in self.domains.keys() {
+      self.domains.write_at(&idx, |d| select_cell.prepare(d));
+    }
+
+    let mut state = WFCState {
+      domains: self.domains,
+      domain_size: self.domain_size,
+      grid: self.grid,
+
+      pick_domain: self.pick_domain,
+      order_values: self.order_values,
+      select_cell,
+      queue: CellQueue::default(),
+      constraint: self.constraint,
+      propagations: self.propagations,
+      changed: self.changed,
+      observer: self.observer,
+    };
+    state.requeue(state.domains.keys());
+    state
+  }
+
+  /// Runs a function on everything known about a cell, or returns `None` if
+  /// the cell doesn't exist
+  fn with_cell<R>(&self, idx: &Idx, mut op: impl FnMut(&Cell<'_, N, Idx>) -> R) -> Option<R> {
+    let neighbours = self.grid.neighbours(idx);
+    self.domains.read_at(idx, |domain| {
+      op(&Cell {
+        idx,
+        domain,
+        neighbours: &neighbours,
+        domains: &self.domains,
+        constraint: self.constraint,
       })
-      .sum();
+    })
+  }
+
+  /// The first of the given cells that can no longer take the tile the sampler
+  /// pins it to, if any
+  fn unpinned<'i>(
+    &self,
+    domains: &CSPDomains<N, Idx>,
+    idxs: impl IntoIterator<Item = &'i Idx>,
+  ) -> Option<&'i Idx>
+  where
+    S: CellSampler<N, Idx>,
+  {
+    idxs.into_iter().find(|&idx| {
+      let neighbours = self.grid.neighbours(idx);
+      let pinned = domains.read_at(idx, |domain| {
+        let cell = Cell {
+          idx,
+          domain,
+          neighbours: &neighbours,
+          domains,
+          constraint: self.constraint,
+        };
+        (self.pick_domain.pinned(&cell)).is_some_and(|tile| !domain.contains(tile))
+      });
+      pinned.unwrap_or(false)
+    })
+  }
+
+  /// Ranks a cell for selection, or `None` if it's already been collapsed
+  fn rank(&self, idx: &Idx) -> Option<C::Rank> {
+    self.with_cell(idx, |cell| {
+      (!cell.domain.is_single()).then(|| self.select_cell.rank(cell))
+    })?
+  }
+
+  /// Ranks every cell into a new queue of cells to collapse
+  fn ranked_queue(&self) -> CellQueue<Idx, C::Rank> {
+    let mut queue = CellQueue::new(self.select_cell.version());
+    for idx in self.domains.keys() {
+      let rank = self.rank(&idx);
+      queue.update(idx, rank);
+    }
+    queue
+  }
+
+  /// Re-ranks every cell that's changed in the queue of cells to collapse
+  fn requeue(&mut self, changed: impl IntoIterator<Item = Idx>) {
+    if self.queue.version() != self.select_cell.version() {
+      self.queue = self.ranked_queue();
+      return;
+    }
+
+    let mut changed: HashSet<_> = changed.into_iter().collect();
+    if self.select_cell.ranks_neighbours() {
+      let neighbours: Vec<_> = (changed.iter())
+        .flat_map(|idx| self.grid.neighbours(idx))
+        .flatten()
+        .collect();
+      changed.extend(neighbours);
+    }
+
+    for idx in changed {
+      let rank = self.rank(&idx);
+      self.queue.update(idx, rank);
+    }
+  }
+}
+
+impl<'a, const N: usize, Idx, G, S, C, O, V> Clone for WFCState<'a, N, Idx, G, S, C, O, V>
+where
+  Idx: Clone + Hash + Eq,
+  S: Clone,
+  C: CellSelector<N, Idx> + Clone,
+  O: Clone,
+  V: Clone,
+{
+  fn clone(&self) -> Self {
+    Self {
+      domains: self.domains.clone(),
+      domain_size: self.domain_size,
+      grid: self.grid,
+
+      pick_domain: self.pick_domain.clone(),
+      order_values: self.order_values.clone(),
+      select_cell: self.select_cell.clone(),
+      queue: self.queue.clone(),
+      constraint: self.constraint,
+      propagations: self.propagations,
+      changed: self.changed.clone(),
+      observer: self.observer.clone(),
+    }
+  }
+}
+
+impl<'a, const N: usize, Idx, G, S, C, O, V> WFCState<'a, N, Idx, G, S, C, O, V>
+where
+  Idx: Clone + Hash + Eq + Send + Sync,
+  G: Grid<N, Idx> + Send + Sync,
+  S: CellSampler<N, Idx> + Clone,
+  C: CellSelector<N, Idx> + Clone,
+  O: Clone,
+  V: Clone,
+{
+  /// Resets every cell in a region so it can take any tile again, restricted
+  /// only by the cells surrounding the region.
+  ///
+  /// This is intended to allow part of a solved state to be regenerated, as
+  /// only the cells in the region will be left to collapse when searching from
+  /// the returned state.
+  pub fn reset_region(&self, region: impl IntoIterator<Item = Idx>) -> Result<Self, AC3ErrorKind> {
+    let domains = self.domains.clone();
+    let mut full = Domain::constraint(self.constraint, self.domain_size);
+    self.pick_domain.prepare(&mut full);
+    self.select_cell.prepare(&mut full);
+    let region: HashSet<_> = (region.into_iter())
+      .filter(|idx| domains.exists(idx))
+      .collect();
+
+    for idx in region.iter() {
+      domains.write_at(idx, |d| *d = full.clone());
+    }
+
+    // supports between the region and its surroundings are now out of date
+    // @note all supports are recounted before removing any unsupported tiles,
+    // so that propagating the removals won't count them twice
+    let mut recounted = vec![];
+    for idx in region.iter() {
+      for (side, optn) in self.grid.neighbours(idx).into_iter().enumerate() {
+        let Some(neighbour) = optn.filter(|idx| domains.exists(idx)) else {
+          continue;
+        };
+        recounted.push((idx.clone(), side, neighbour.clone()));
+        if !region.contains(&neighbour) {
+          recounted.push((neighbour, self.grid.opposite(side), idx.clone()));
+        }
+      }
+    }
+
+    let snapshot = domains.clone();
+    for (idx, side, neighbour) in recounted.iter() {
+      let neighbour = snapshot.read_at(neighbour, Domain::clone).unwrap();
+      domains.write_at(idx, |d| d.recount_side(*side, self.constraint, &neighbour));
+    }
+
+    let mut updates = vec![];
+    for (idx, side, _) in recounted.iter() {
+      let removed = domains
+        .write_at(idx, |d| d.remove_unsupported(*side))
+        .unwrap();
+      updates.extend(self.grid.updates_for(idx, removed));
+    }
+
+    let propagation = propagate(
+      &domains,
+      self.domain_size,
+      self.grid,
+      self.constraint,
+      updates,
+    )?;
+    if !domains.all(|d| !d.is_empty()) {
+      return Err(AC3ErrorKind::InconsistentChoice);
+    }
+
+    let mut state = Self {
+      domains,
+      domain_size: self.domain_size,
+      grid: self.grid,
 
-    [self.domain_size - mrv, degree]
+      pick_domain: self.pick_domain.clone(),
+      order_values: self.order_values.clone(),
+      select_cell: self.select_cell.clone(),
+      queue: self.queue.clone(),
+      constraint: self.constraint,
+      propagations: self.propagations + propagation.tasks,
+      changed: HashSet::new(),
+      observer: self.observer.clone(),
+    };
+    let recounted = recounted.into_iter().map(|(idx, _, _)| idx);
+    state.changed = (region.into_iter())
+      .chain(recounted)
+      .chain(propagation.changed)
+      .collect();
+    state.requeue(state.changed.clone());
+    Ok(state)
   }
 }
 
-impl<'a, const N: usize, Idx, G, S> State for WFCState<'a, N, Idx, G, S>
+impl<'a, const N: usize, Idx, G, S, C, O, V> State for WFCState<'a, N, Idx, G, S, C, O, V>
 where
   Idx: Clone + Hash + Eq + Send + Sync,
   G: Grid<N, Idx> + Send + Sync,
-  S: Sampler + Clone,
+  S: CellSampler<N, Idx> + Clone,
+  C: CellSelector<N, Idx> + Clone,
+  O: WfcObserver<N, Idx> + Clone,
+  V: ValueOrder<N, Idx> + Clone,
 {
   type Action = (Idx, usize);
   type Error = WFCError<Idx>;
@@ -77,38 +390,75 @@ where
 
   type ActnIter = Vec<(Idx, usize)>;
   fn get_actions(&self) -> Self::ActnIter {
-    let idxs = self.domains.keys();
-    let max_idx = idxs
-      .into_iter()
-      .max_by_key(|idx| self.ac3_heuristic(idx))
-      .unwrap();
+    // @note the selector may have learnt since this state was queued, in which
+    // case every cell has to be ranked again
+    let stale = (self.queue.version() != self.select_cell.version()).then(|| self.ranked_queue());
+    let queue = stale.as_ref().unwrap_or(&self.queue);
+    let Some(max_idx) = queue.peek() else {
+      return vec![];
+    };
 
     self
       .domains
-      .read_at(&max_idx, |d| {
+      .read_at(max_idx, |d| {
         d.iter().map(|tile| (max_idx.clone(), tile)).collect()
       })
       .unwrap()
   }
 
-  type PickError = WFCError<Idx>;
+  type PickError = PickActionError;
   fn pick_action<'b>(
     &'b mut self,
     actions: impl IntoIterator<Item = &'b Self::Action>,
   ) -> Result<Self::Action, Self::PickError> {
     let actions: Vec<_> = actions.into_iter().collect();
     if actions.is_empty() {
-      return Err(WFCError::PickActionError("No actions available".to_owned()));
+      return Err(PickActionError::NoActions);
     }
 
     let (idx, _) = actions[0];
-    let tiles: Vec<_> = actions.into_iter().map(|(_, tile)| *tile).collect();
-    Ok((idx.clone(), self.pick_domain.sample(&tiles)))
+    let neighbours = self.grid.neighbours(idx);
+    let mut tiles: Vec<_> = actions.into_iter().map(|(_, tile)| *tile).collect();
+    while !tiles.is_empty() {
+      // @note the cell is built from individual fields, so the sampler can be
+      // borrowed mutably whilst the cell is borrowed
+      let tile = self.domains.read_at(idx, |domain| {
+        let cell = Cell {
+          idx,
+          domain,
+          neighbours: &neighbours,
+          domains: &self.domains,
+          constraint: self.constraint,
+        };
+        if let Some(tile) = self.pick_domain.pinned(&cell) {
+          return Ok(tile);
+        }
+        let candidates = self.order_values.candidates(&cell, &tiles);
+        self.pick_domain.sample_cell(&cell, &candidates)
+      });
+      let tile = tile
+        .ok_or(PickActionError::MissingCell)?
+        .map_err(PickActionError::Sample)?;
+      // @note a pinned tile may have been removed or vetoed already
+      if !tiles.contains(&tile) {
+        return Err(PickActionError::Sample(SampleError::Unavailable(tile)));
+      }
+      if self.observer.on_select(idx, tile) {
+        return Ok((idx.clone(), tile));
+      }
+      tiles.retain(|&other| other != tile);
+    }
+
+    Err(PickActionError::Vetoed)
+  }
+
+  fn allows(&self, (idx, tile): &Self::Action) -> bool {
+    self.observer.on_select(idx, *tile)
   }
 
   type TakeError = AC3Error<Idx>;
   fn take_action(&self, (idx, tile): &Self::Action) -> Result<Self, Self::TakeError> {
-    let domains = ac3(
+    let (domains, propagation) = ac3(
       self.domains.clone(),
       self.domain_size,
       self.grid,
@@ -116,15 +466,191 @@ where
       idx,
       *tile,
     )
-    .map_err(|kind| AC3Error::new(idx.clone(), *tile, kind))?;
+    .map_err(|e| {
+      if let Some((wiped, side)) = e.wipeout.as_ref() {
+        if let Some(neighbour) = &self.grid.neighbours(wiped)[*side] {
+          let opposite = self.grid.opposite(*side);
+          (self.select_cell).wiped_out([(wiped, *side), (neighbour, opposite)]);
+        }
+      }
+
+      let error = AC3Error::new(idx.clone(), *tile, e.kind).with_wipeout(e.wipeout);
+      self.observer.on_contradiction(&error);
+      error
+    })?;
+    // @note cells collapsed by propagation are never sampled, so a pinned tile
+    // removed by propagation has to be caught here
+    if self.unpinned(&domains, &propagation.changed).is_some() {
+      let error = AC3Error::new(idx.clone(), *tile, AC3ErrorKind::InconsistentChoice);
+      self.observer.on_contradiction(&error);
+      return Err(error);
+    }
+    self.observer.on_propagate(idx, *tile, &domains);
 
-    Ok(Self {
+    let mut state = Self {
       domains,
       domain_size: self.domain_size,
       grid: self.grid,
 
       pick_domain: self.pick_domain.clone(),
+      order_values: self.order_values.clone(),
+      select_cell: self.select_cell.clone(),
+      queue: self.queue.clone(),
       constraint: self.constraint,
-    })
+      propagations: self.propagations + propagation.tasks,
+      changed: propagation.changed,
+      observer: self.observer.clone(),
+    };
+    for changed in state.changed.iter() {
+      if let Some(Some(tile)) = state.domains.read_at(changed, Domain::single_item) {
+        state.pick_domain.collapsed(changed, tile);
+      }
+    }
+    state.requeue(state.changed.clone());
+    Ok(state)
+  }
+
+  fn propagation_tasks(&self) -> usize {
+    self.propagations
+  }
+
+  fn backtracked(&self, depth: usize) {
+    self.observer.on_backtrack(depth);
+  }
+
+  fn restarted(&mut self, last: &Self) {
+    self.pick_domain.restarted(&last.pick_domain);
+  }
+}
+
+impl<'a, const N: usize, Idx, G, S, C, O, V> Canonical for WFCState<'a, N, Idx, G, S, C, O, V>
+where
+  Idx: Clone + Ord + Hash + Send + Sync,
+  G: Grid<N, Idx> + Send + Sync,
+  S: CellSampler<N, Idx> + Clone,
+  C: CellSelector<N, Idx> + Clone,
+  O: WfcObserver<N, Idx> + Clone,
+  V: ValueOrder<N, Idx> + Clone,
+{
+  /// The tile assigned to each cell, ordered by index
+  type Key = Option<Vec<(Idx, usize)>>;
+  fn canonical(&self) -> Self::Key {
+    self.domains.assignment()
+  }
+}
+
+impl<'a, const N: usize, Idx, G, S, C, O, V> Evented for WFCState<'a, N, Idx, G, S, C, O, V>
+where
+  Idx: Clone + Hash + Eq + Send + Sync,
+  G: Grid<N, Idx> + Send + Sync,
+  S: CellSampler<N, Idx> + Clone,
+  C: CellSelector<N, Idx> + Clone,
+  O: WfcObserver<N, Idx> + Clone,
+  V: ValueOrder<N, Idx> + Clone,
+{
+  type Idx = Idx;
+  /// @note only the cells changed by the action that produced `next` are
+  /// compared, in order of their stable hash so events are reproducible
+  fn events(&self, next: &Self) -> Vec<WFCEvent<Idx>> {
+    let mut changed: Vec<_> = next.changed.iter().collect();
+    changed.sort_by_cached_key(|idx| seeded_hash(0, idx));
+
+    let mut events = vec![];
+    for idx in changed {
+      let before: Vec<_> = (self.domains)
+        .read_at(idx, |d| d.iter().collect())
+        .unwrap_or_else(|| (0..self.domain_size).collect());
+      let Some((banned, collapsed)) = next.domains.read_at(idx, |d| {
+        let banned: Vec<_> = before
+          .iter()
+          .filter(|&&tile| !d.contains(tile))
+          .copied()
+          .collect();
+        (banned, d.single_item().filter(|_| before.len() > 1))
+      }) else {
+        continue;
+      };
+
+      if !banned.is_empty() {
+        events.push(WFCEvent::TilesBanned(idx.clone(), banned));
+      }
+      if let Some(tile) = collapsed {
+        events.push(WFCEvent::CellCollapsed(idx.clone(), tile));
+      }
+    }
+    events
+  }
+}
+
+/// A saved form of a [`WFCState`], without the grid or constraint it borrows.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+  serialize = "Idx: Serialize, S: Serialize, C: Serialize, V: Serialize",
+  deserialize = "Idx: Deserialize<'de> + Hash + Eq, S: Deserialize<'de>, C: Deserialize<'de>, \
+                 V: Deserialize<'de>"
+))]
+pub struct SavedState<const N: usize, Idx, S, C, V> {
+  domains: CSPDomains<N, Idx>,
+  domain_size: usize,
+  pick_domain: S,
+  order_values: V,
+  select_cell: C,
+  propagations: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<'a, const N: usize, Idx, G, S, C, O, V> Checkpoint for WFCState<'a, N, Idx, G, S, C, O, V>
+where
+  Idx: Clone + Hash + Eq + Serialize + DeserializeOwned,
+  G: Grid<N, Idx>,
+  S: CellSampler<N, Idx> + Clone + Serialize + DeserializeOwned,
+  C: CellSelector<N, Idx> + Clone + Serialize + DeserializeOwned,
+  O: Default,
+  V: Clone + Serialize + DeserializeOwned,
+{
+  /// @note observers aren't saved, so restored states use a default observer,
+  /// and the queue of cells to collapse is rebuilt from the saved domains
+  type Saved = SavedState<N, Idx, S, C, V>;
+  type Context = (&'a G, &'a Constraint<N>);
+
+  fn save(&self) -> Self::Saved {
+    SavedState {
+      domains: self.domains.clone(),
+      domain_size: self.domain_size,
+      pick_domain: self.pick_domain.clone(),
+      order_values: self.order_values.clone(),
+      select_cell: self.select_cell.clone(),
+      propagations: self.propagations,
+    }
+  }
+
+  fn restore(saved: Self::Saved, (grid, constraint): Self::Context) -> Self {
+    let mut state = Self {
+      domains: saved.domains,
+      domain_size: saved.domain_size,
+      grid,
+
+      pick_domain: saved.pick_domain,
+      order_values: saved.order_values,
+      select_cell: saved.select_cell,
+      queue: CellQueue::default(),
+      constraint,
+      propagations: saved.propagations,
+      changed: HashSet::new(),
+      observer: O::default(),
+    };
+    for idx in state.domains.keys() {
+      state.domains.write_at(&idx, |d| {
+        state.pick_domain.prepare(d);
+        state.select_cell.prepare(d);
+      });
+    }
+    state.requeue(state.domains.keys());
+    state
+  }
+
+  fn relink(&mut self, other: &Self) {
+    self.select_cell.relink(&other.select_cell);
   }
 }
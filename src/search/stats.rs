@@ -0,0 +1,58 @@
+use super::State;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Statistics collected by a search whilst it runs.
+///
+/// These are updated as the search is iterated, so they can be read at any
+/// point to compare the progress of different searches on the same problem.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchStats {
+  /// The number of actions taken from a state
+  pub decisions: usize,
+  /// The number of times a state was abandoned for an earlier one
+  pub backtracks: usize,
+  /// The number of times the search started again from its initial state
+  pub restarts: usize,
+  /// The number of actions that failed to produce a valid state
+  pub contradictions: usize,
+  /// The number of propagation tasks run to produce valid states
+  pub propagation_tasks: usize,
+  /// The greatest number of actions taken from the initial state
+  pub peak_depth: usize,
+  /// The total time spent generating results
+  pub elapsed: Duration,
+}
+
+impl SearchStats {
+  /// Records the result of taking an action from `state`, where `depth` is
+  /// the number of actions taken to produce the result.
+  pub(crate) fn record<S: State, E>(&mut self, state: &S, result: &Result<S, E>, depth: usize) {
+    self.decisions += 1;
+    match result {
+      Err(_) => self.contradictions += 1,
+      Ok(new_state) => {
+        self.propagation_tasks += new_state
+          .propagation_tasks()
+          .saturating_sub(state.propagation_tasks());
+        self.peak_depth = self.peak_depth.max(depth);
+      }
+    }
+  }
+
+  /// Combines statistics from two searches run side by side.<br>
+  /// The elapsed time is taken as the longest of the two.
+  pub fn merge(&self, other: &Self) -> Self {
+    Self {
+      decisions: self.decisions + other.decisions,
+      backtracks: self.backtracks + other.backtracks,
+      restarts: self.restarts + other.restarts,
+      contradictions: self.contradictions + other.contradictions,
+      propagation_tasks: self.propagation_tasks + other.propagation_tasks,
+      peak_depth: self.peak_depth.max(other.peak_depth),
+      elapsed: self.elapsed.max(other.elapsed),
+    }
+  }
+}
@@ -0,0 +1,136 @@
+use super::{PickActionError, State};
+use std::collections::VecDeque;
+
+/// An event that occurs whilst generating a state one step at a time
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WFCEvent<Idx> {
+  /// The cell at the index was collapsed to a single tile
+  CellCollapsed(Idx, usize),
+  /// Tiles were removed from the domain of the cell at the index
+  TilesBanned(Idx, Vec<usize>),
+  /// The last action taken led to a contradiction, and was discarded
+  Contradiction,
+  /// No action could be picked for the current state, so it was abandoned
+  PickFailed(PickActionError),
+  /// The search returned to the state after the given number of actions
+  Backtracked(usize),
+}
+
+/// A state whose transitions can be described as a series of events
+pub trait Evented: State<PickError = PickActionError> {
+  type Idx;
+  /// Describes all changes made by transitioning from this state to `next`,
+  /// which should be produced by taking an action from this state
+  fn events(&self, next: &Self) -> Vec<WFCEvent<Self::Idx>>;
+}
+
+/// Performs a Depth First Search of possible states, one action at a time.
+///
+/// Rather than outputting states, this outputs the events that occur whilst
+/// searching, i.e. to allow generation to be animated. The search stops at the
+/// first goal state, which can then be fetched via [`Steps::solution`].
+///
+/// Whenever the search backtracks, the state it returns to can be fetched via
+/// [`Steps::current`].
+pub struct Steps<S: Evented> {
+  history: Vec<(S, Vec<S::Action>)>,
+  solution: Option<S>,
+  events: VecDeque<WFCEvent<S::Idx>>,
+}
+
+impl<S: Evented> Steps<S>
+where
+  S::Action: Eq,
+{
+  pub fn new(start: S) -> Self {
+    let mut steps = Self {
+      history: vec![],
+      solution: None,
+      events: VecDeque::new(),
+    };
+    match start.is_goal() {
+      true => steps.solution = Some(start),
+      false => {
+        let actns = start.get_actions().into_iter().collect();
+        steps.history.push((start, actns));
+      }
+    }
+    steps
+  }
+
+  /// The state the search is currently at
+  pub fn current(&self) -> Option<&S> {
+    (self.solution.as_ref()).or(self.history.last().map(|(state, _)| state))
+  }
+
+  /// The goal state found by the search, if it's found one
+  pub fn solution(&self) -> Option<&S> {
+    self.solution.as_ref()
+  }
+
+  /// Returns to the previous state, once the current state has been abandoned
+  fn backtrack(&mut self) -> Option<Vec<WFCEvent<S::Idx>>> {
+    let depth = self.history.len().checked_sub(1)?;
+    self.history[depth].0.backtracked(depth);
+    Some(vec![WFCEvent::Backtracked(depth)])
+  }
+
+  /// Takes a single action (or backtracks once), returning the events that
+  /// occurred as a result.<br>
+  /// Returns `None` once a goal is found or there are no states left to search.
+  pub fn step(&mut self) -> Option<Vec<WFCEvent<S::Idx>>> {
+    if self.solution.is_some() {
+      return None;
+    }
+
+    let (mut state, mut actns) = self.history.pop()?;
+    if actns.is_empty() {
+      return self.backtrack();
+    }
+
+    let choice = match state.pick_action(actns.iter()) {
+      Err(e) => {
+        let mut events = vec![WFCEvent::PickFailed(e)];
+        events.extend(self.backtrack().into_iter().flatten());
+        return Some(events);
+      }
+      Ok(choice) => choice,
+    };
+    let i = actns.iter().position(|actn| actn == &choice).unwrap();
+    let actn = actns.swap_remove(i);
+
+    let result = state.take_action(&actn);
+    let new_state = match result {
+      Err(_) => {
+        self.history.push((state, actns));
+        return Some(vec![WFCEvent::Contradiction]);
+      }
+      Ok(new_state) => new_state,
+    };
+
+    let events = state.events(&new_state);
+    self.history.push((state, actns));
+    match new_state.is_goal() {
+      true => self.solution = Some(new_state),
+      false => {
+        let new_actns = new_state.get_actions().into_iter().collect();
+        self.history.push((new_state, new_actns));
+      }
+    }
+    Some(events)
+  }
+}
+
+impl<S: Evented> Iterator for Steps<S>
+where
+  S::Action: Eq,
+{
+  type Item = WFCEvent<S::Idx>;
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.events.is_empty() {
+      let events = self.step()?;
+      self.events.extend(events);
+    }
+    self.events.pop_front()
+  }
+}
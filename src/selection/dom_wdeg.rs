@@ -0,0 +1,129 @@
+use super::{Cell, CellSelector};
+use crate::utility::TotalF64;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+  cmp::Reverse,
+  collections::HashMap,
+  hash::Hash,
+  sync::{Arc, RwLock},
+};
+
+/// Weights learnt from the wipeouts seen so far
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  feature = "serde",
+  serde(bound(
+    serialize = "Idx: Serialize + Hash + Eq",
+    deserialize = "Idx: Deserialize<'de> + Hash + Eq"
+  ))
+)]
+struct Weights<Idx> {
+  /// The number of wipeouts each side of a cell has been involved in
+  edges: HashMap<(Idx, usize), usize>,
+  wipeouts: usize,
+}
+
+impl<Idx> Default for Weights<Idx> {
+  fn default() -> Self {
+    Self {
+      edges: HashMap::new(),
+      wipeouts: 0,
+    }
+  }
+}
+
+/// Selects the cell with the fewest tiles left relative to its weighted degree
+/// (dom/wdeg), learning from contradictions as the search runs.
+///
+/// Every constraint between neighbouring cells starts with a weight of 1, that
+/// is incremented each time the constraint wipes out a domain. A cell's
+/// weighted degree is then the sum of the weights of the constraints to its
+/// neighbours that haven't been collapsed yet.
+///
+/// The weights are shared between every clone of the selector, so they persist
+/// across backtracks and restarts within the same search. Create a new
+/// selector to start learning from scratch.
+///
+/// The weights are saved alongside the selector, and searches share them
+/// between the states they restore again (see [`CellSelector::relink`]).
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  feature = "serde",
+  serde(bound(
+    serialize = "Idx: Serialize + Hash + Eq",
+    deserialize = "Idx: Deserialize<'de> + Hash + Eq"
+  ))
+)]
+pub struct DomWdeg<Idx> {
+  weights: Arc<RwLock<Weights<Idx>>>,
+}
+
+impl<Idx> DomWdeg<Idx> {
+  pub fn new() -> Self {
+    Self {
+      weights: Arc::new(RwLock::new(Weights::default())),
+    }
+  }
+
+  /// The number of wipeouts learnt from so far
+  pub fn wipeouts(&self) -> usize {
+    self.weights.read().unwrap().wipeouts
+  }
+}
+
+impl<Idx> Clone for DomWdeg<Idx> {
+  fn clone(&self) -> Self {
+    Self {
+      weights: self.weights.clone(),
+    }
+  }
+}
+
+impl<Idx> Default for DomWdeg<Idx> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<Idx: Hash + Eq> DomWdeg<Idx> {
+  /// The weight of the constraint on a given side of a cell
+  pub fn weight(&self, idx: Idx, side: usize) -> usize {
+    let weights = self.weights.read().unwrap();
+    1 + weights.edges.get(&(idx, side)).copied().unwrap_or(0)
+  }
+}
+
+impl<const N: usize, Idx: Clone + Hash + Eq> CellSelector<N, Idx> for DomWdeg<Idx> {
+  type Rank = Reverse<TotalF64>;
+  fn rank(&self, cell: &Cell<'_, N, Idx>) -> Self::Rank {
+    let wdeg: usize = (0..N)
+      .filter(|&side| cell.neighbour(side, |d| !d.is_single()).unwrap_or(false))
+      .map(|side| self.weight(cell.idx.clone(), side))
+      .sum();
+
+    Reverse(TotalF64(cell.domain.len() as f64 / wdeg.max(1) as f64))
+  }
+
+  fn ranks_neighbours(&self) -> bool {
+    true
+  }
+
+  fn wiped_out(&self, constraint: [(&Idx, usize); 2]) {
+    let mut weights = self.weights.write().unwrap();
+    for (idx, side) in constraint {
+      *weights.edges.entry((idx.clone(), side)).or_insert(0) += 1;
+    }
+    weights.wipeouts += 1;
+  }
+
+  fn version(&self) -> usize {
+    self.wipeouts()
+  }
+
+  fn relink(&mut self, other: &Self) {
+    self.weights = other.weights.clone();
+  }
+}
@@ -0,0 +1,58 @@
+use super::{seeded_hash, Cell, CellSelector};
+use crate::{consistency::Domain, utility::TotalF64};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, hash::Hash, sync::Arc};
+
+/// The largest amount of noise added to a cell's entropy to break ties
+const NOISE: f64 = 1e-6;
+
+/// Selects the cell with the lowest Shannon entropy, where each tile is
+/// weighted by how frequently it should appear (i.e. the weights given to a
+/// `Weighted` sampler).
+///
+/// For a cell with tile weights `w`, the entropy is given by:
+/// `H = log(Σw) - Σ(w log w) / Σw`
+///
+/// The sums are kept up to date within each domain as tiles are removed, so
+/// ranking a cell doesn't require iterating over its tiles. Ties are broken by
+/// adding a small amount of noise to the entropy, fixed by a seed.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Entropy {
+  weights: Arc<[f64]>,
+  seed: u64,
+}
+
+impl Entropy {
+  pub fn new(weights: impl Into<Arc<[f64]>>) -> Self {
+    Self {
+      weights: weights.into(),
+      seed: 0,
+    }
+  }
+
+  /// Sets the seed for the noise used to break ties
+  pub fn with_seed(self, seed: u64) -> Self {
+    Self { seed, ..self }
+  }
+}
+
+impl<const N: usize, Idx: Hash> CellSelector<N, Idx> for Entropy {
+  type Rank = Reverse<TotalF64>;
+  fn rank(&self, cell: &Cell<'_, N, Idx>) -> Self::Rank {
+    // @note domains inserted during propagation won't have been prepared
+    let entropy = cell.domain.entropy().unwrap_or_else(|| {
+      let mut domain = cell.domain.clone();
+      domain.set_weights(self.weights.clone());
+      domain.entropy().unwrap_or(0.0)
+    });
+
+    let noise = seeded_hash(self.seed, cell.idx) as f64 / u64::MAX as f64 * NOISE;
+    Reverse(TotalF64(entropy + noise))
+  }
+
+  fn prepare(&self, domain: &mut Domain<N>) {
+    domain.set_weights(self.weights.clone());
+  }
+}
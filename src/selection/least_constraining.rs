@@ -0,0 +1,90 @@
+use super::{Cell, ValueOrder};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// How an ordering of tiles is combined with the sampler
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Combine {
+  /// Only the best tiles are sampled from, so the sampler just breaks ties
+  #[default]
+  Tiebreak,
+  /// Every tile is sampled from, with better tiles repeated more often
+  Weighting,
+}
+
+/// Prefers the tiles that remove the fewest supports from neighbouring cells
+/// (Least Constraining Value), leaving the most options open for later.
+///
+/// Collapsing a cell to a tile removes every other tile in its domain, and so
+/// every support those tiles gave to tiles in neighbouring domains. The number
+/// of supports removed for each tile is counted using the constraint.
+///
+/// With [`Combine::Weighting`], each tile is repeated once for every tile that
+/// removes more supports than it, plus once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LeastConstraining {
+  combine: Combine,
+}
+
+impl LeastConstraining {
+  pub fn new(combine: Combine) -> Self {
+    Self { combine }
+  }
+
+  /// The number of supports that would be removed from neighbouring cells by
+  /// collapsing the cell to each of the tiles
+  fn supports_removed<const N: usize, Idx>(cell: &Cell<'_, N, Idx>, tiles: &[usize]) -> Vec<usize>
+  where
+    Idx: Hash + Eq,
+  {
+    let mut removed = vec![0; tiles.len()];
+    for side in 0..N {
+      let Some(supports) = cell.neighbour(side, |d| {
+        (cell.domain.iter())
+          .map(|tile0| {
+            let count = d
+              .iter()
+              .filter(|&tile1| cell.constraint[(tile0, tile1, side)]);
+            (tile0, count.count())
+          })
+          .collect::<Vec<_>>()
+      }) else {
+        continue;
+      };
+
+      let total: usize = supports.iter().map(|(_, count)| count).sum();
+      for (i, &tile) in tiles.iter().enumerate() {
+        let kept = (supports.iter())
+          .find(|(tile0, _)| *tile0 == tile)
+          .map_or(0, |(_, count)| *count);
+        removed[i] += total - kept;
+      }
+    }
+    removed
+  }
+}
+
+impl<const N: usize, Idx: Hash + Eq> ValueOrder<N, Idx> for LeastConstraining {
+  fn candidates(&self, cell: &Cell<'_, N, Idx>, tiles: &[usize]) -> Vec<usize> {
+    let removed = Self::supports_removed(cell, tiles);
+    let Some(&fewest) = removed.iter().min() else {
+      return vec![];
+    };
+
+    match self.combine {
+      Combine::Tiebreak => (tiles.iter().zip(&removed))
+        .filter(|(_, &count)| count == fewest)
+        .map(|(&tile, _)| tile)
+        .collect(),
+      Combine::Weighting => (tiles.iter().zip(&removed))
+        .flat_map(|(&tile, &count)| {
+          let repeats = 1 + removed.iter().filter(|&&other| other > count).count();
+          std::iter::repeat_n(tile, repeats)
+        })
+        .collect(),
+    }
+  }
+}
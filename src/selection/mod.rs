@@ -0,0 +1,111 @@
+//! Cell selection decides which cell should be collapsed next, in the same way
+//! that sampling decides which tile a cell should be collapsed to.
+
+mod dom_wdeg;
+pub use dom_wdeg::DomWdeg;
+mod entropy;
+pub use entropy::Entropy;
+mod queue;
+pub(crate) use queue::CellQueue;
+mod least_constraining;
+pub use least_constraining::{Combine, LeastConstraining};
+mod mrv;
+pub use mrv::{Mrv, MrvDegree};
+mod random_order;
+pub use random_order::RandomOrder;
+mod scanline;
+pub use scanline::Scanline;
+mod spiral;
+pub use spiral::Spiral;
+
+use crate::{
+  consistency::{CSPDomains, Constraint, Domain},
+  utility::StableHasher,
+};
+use std::hash::{Hash, Hasher};
+
+/// Everything known about a cell when ranking it for selection
+pub struct Cell<'a, const N: usize, Idx> {
+  /// The index of the cell
+  pub idx: &'a Idx,
+  /// The tiles the cell can still take
+  pub domain: &'a Domain<N>,
+  /// The neighbours of the cell on each side
+  pub neighbours: &'a [Option<Idx>; N],
+  /// The domains of every cell, read via [`Cell::neighbour`]
+  pub(crate) domains: &'a CSPDomains<N, Idx>,
+  /// A constraint on which tiles can be placed next to each other
+  pub constraint: &'a Constraint<N>,
+}
+
+impl<const N: usize, Idx: Hash + Eq> Cell<'_, N, Idx> {
+  /// Runs a function on the domain of the neighbour on a side, or returns
+  /// `None` if there's no neighbour on that side
+  pub fn neighbour<R>(&self, side: usize, op: impl FnMut(&Domain<N>) -> R) -> Option<R> {
+    let idx = self.neighbours.get(side)?.as_ref()?;
+    self.domains.read_at(idx, op)
+  }
+}
+
+/// Ranks cells that haven't been collapsed yet, such that the highest ranked
+/// cell will be collapsed next.
+pub trait CellSelector<const N: usize, Idx> {
+  type Rank: Ord + Clone;
+  fn rank(&self, cell: &Cell<'_, N, Idx>) -> Self::Rank;
+
+  /// Prepares a domain before it's used by this selector, i.e. to keep track
+  /// of anything needed to rank it as tiles are removed.
+  fn prepare(&self, _domain: &mut Domain<N>) {}
+
+  /// Whether a cell's rank depends on the domains of its neighbours.<br>
+  /// If so, cells are re-ranked whenever one of their neighbours changes, as
+  /// well as when they change themselves.
+  fn ranks_neighbours(&self) -> bool {
+    false
+  }
+
+  /// Called when propagation wipes out a cell's domain, with the cells either
+  /// side of the constraint that removed its last tiles (the wiped out cell
+  /// first), each alongside its side facing the other.
+  fn wiped_out(&self, _constraint: [(&Idx, usize); 2]) {}
+
+  /// Changes whenever ranks change for any reason other than domains changing
+  /// (i.e. learning from wipeouts), at which point every cell is re-ranked.
+  fn version(&self) -> usize {
+    0
+  }
+
+  /// Shares anything learnt with `other`, where both were shared before being
+  /// restored from a checkpoint (which gives each of them its own copy).
+  fn relink(&mut self, _other: &Self)
+  where
+    Self: Sized,
+  {
+  }
+}
+
+/// Orders the tiles a cell could be collapsed to before they're sampled from,
+/// by narrowing them down or repeating them.
+///
+/// As samplers only see a slice of tiles, repeating a tile makes it more likely
+/// to be picked by a random sampler (i.e. `Uniform` or `Weighted`), whilst
+/// narrowing the tiles down leaves the sampler to break ties.
+pub trait ValueOrder<const N: usize, Idx> {
+  /// The tiles to sample from, out of those the cell can still take
+  fn candidates(&self, cell: &Cell<'_, N, Idx>, tiles: &[usize]) -> Vec<usize>;
+}
+
+/// Leaves the tiles to the sampler alone
+impl<const N: usize, Idx> ValueOrder<N, Idx> for () {
+  fn candidates(&self, _cell: &Cell<'_, N, Idx>, tiles: &[usize]) -> Vec<usize> {
+    tiles.to_vec()
+  }
+}
+
+/// Hashes an index alongside a seed, such that the same seed and index will
+/// always give the same hash, in every build
+pub(crate) fn seeded_hash(seed: u64, idx: &impl Hash) -> u64 {
+  let mut hasher = StableHasher::default();
+  (seed, idx).hash(&mut hasher);
+  hasher.finish()
+}
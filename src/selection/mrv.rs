@@ -0,0 +1,55 @@
+use super::{Cell, CellSelector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, hash::Hash};
+
+/// Selects the cell with the fewest tiles left (Minimum Remaining Values)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Mrv;
+
+impl<const N: usize, Idx> CellSelector<N, Idx> for Mrv {
+  type Rank = Reverse<usize>;
+  fn rank(&self, cell: &Cell<'_, N, Idx>) -> Self::Rank {
+    Reverse(cell.domain.len())
+  }
+}
+
+/// Selects the cell with the fewest tiles left, breaking ties by the number of
+/// pairs of tiles that can still be placed next to its neighbours.
+///
+/// Cells that have been collapsed to a single tile are never ranked, so the
+/// cells compared always have at least 2 tiles left. The exception is a cell
+/// whose domain has been emptied, which is ranked above every other cell so
+/// that the contradiction is reached straight away.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MrvDegree;
+
+impl<const N: usize, Idx: Hash + Eq> CellSelector<N, Idx> for MrvDegree {
+  type Rank = (Reverse<usize>, usize);
+  fn rank(&self, cell: &Cell<'_, N, Idx>) -> Self::Rank {
+    let values: Vec<_> = cell.domain.iter().collect();
+
+    let degree = (0..N)
+      .filter_map(|side| {
+        cell.neighbour(side, |d| {
+          d.iter()
+            .map(|tile1| {
+              values
+                .iter()
+                .filter(|&&tile0| cell.constraint[(tile0, tile1, side)])
+                .count()
+            })
+            .sum::<usize>()
+        })
+      })
+      .sum();
+
+    (Reverse(values.len()), degree)
+  }
+
+  fn ranks_neighbours(&self) -> bool {
+    true
+  }
+}
@@ -0,0 +1,120 @@
+use super::seeded_hash;
+use std::{
+  cmp::{Ordering, Reverse},
+  collections::{BinaryHeap, HashMap},
+  hash::Hash,
+};
+
+/// A cell queued for selection at a given rank
+#[derive(Clone, Debug)]
+struct Queued<Idx, R> {
+  rank: R,
+  /// A fixed hash of the index, to break ties between equally ranked cells
+  tiebreak: u64,
+  order: usize,
+  idx: Idx,
+}
+
+impl<Idx, R: Ord> Queued<Idx, R> {
+  /// Ranks by rank, then by the hash of the index.<br>
+  /// @note ties aren't broken by which cell was queued first, as cells changed
+  /// by parallel propagation are queued in no particular order
+  fn key(&self) -> (&R, u64, Reverse<usize>) {
+    (&self.rank, self.tiebreak, Reverse(self.order))
+  }
+}
+
+impl<Idx, R: Ord> PartialEq for Queued<Idx, R> {
+  fn eq(&self, other: &Self) -> bool {
+    self.key() == other.key()
+  }
+}
+impl<Idx, R: Ord> Eq for Queued<Idx, R> {}
+
+impl<Idx, R: Ord> PartialOrd for Queued<Idx, R> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<Idx, R: Ord> Ord for Queued<Idx, R> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.key().cmp(&other.key())
+  }
+}
+
+/// A priority queue of the cells left to collapse, that's updated only for
+/// the cells whose rank changes.
+///
+/// Re-ranking a cell pushes a new entry rather than searching the heap for the
+/// old one, so stale entries are skipped when they reach the top of the heap,
+/// and cleared out altogether once they outnumber the live entries.
+#[derive(Clone, Debug)]
+pub(crate) struct CellQueue<Idx, R> {
+  heap: BinaryHeap<Queued<Idx, R>>,
+  /// The order of the live entry for each queued cell
+  live: HashMap<Idx, usize>,
+  queued: usize,
+  /// The version of the selector the cells were ranked by
+  version: usize,
+}
+
+impl<Idx, R> Default for CellQueue<Idx, R> {
+  fn default() -> Self {
+    Self::new(0)
+  }
+}
+
+impl<Idx, R> CellQueue<Idx, R> {
+  pub(crate) fn new(version: usize) -> Self {
+    Self {
+      heap: BinaryHeap::new(),
+      live: HashMap::new(),
+      queued: 0,
+      version,
+    }
+  }
+
+  pub(crate) fn version(&self) -> usize {
+    self.version
+  }
+}
+
+impl<Idx: Clone + Hash + Eq, R: Ord> CellQueue<Idx, R> {
+  /// The highest ranked cell in the queue
+  pub(crate) fn peek(&self) -> Option<&Idx> {
+    self.heap.peek().map(|queued| &queued.idx)
+  }
+
+  /// Re-ranks a cell, or removes it from the queue if `rank` is `None`
+  pub(crate) fn update(&mut self, idx: Idx, rank: Option<R>) {
+    match rank {
+      None => {
+        self.live.remove(&idx);
+      }
+      Some(rank) => {
+        self.live.insert(idx.clone(), self.queued);
+        self.heap.push(Queued {
+          rank,
+          tiebreak: seeded_hash(0, &idx),
+          order: self.queued,
+          idx,
+        });
+        self.queued += 1;
+      }
+    }
+
+    if self.heap.len() > 2 * self.live.len() {
+      let live = &self.live;
+      self
+        .heap
+        .retain(|queued| live.get(&queued.idx) == Some(&queued.order));
+    }
+    while let Some(queued) = self.heap.peek() {
+      if self.live.get(&queued.idx) == Some(&queued.order) {
+        break;
+      }
+      self.heap.pop();
+    }
+  }
+}
@@ -0,0 +1,27 @@
+use super::{seeded_hash, Cell, CellSelector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// Selects cells in a random order, fixed by a seed.
+///
+/// Each cell's rank is a hash of its index and the seed, so the same seed will
+/// always produce the same order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RandomOrder {
+  seed: u64,
+}
+
+impl RandomOrder {
+  pub fn new(seed: u64) -> Self {
+    Self { seed }
+  }
+}
+
+impl<const N: usize, Idx: Hash> CellSelector<N, Idx> for RandomOrder {
+  type Rank = u64;
+  fn rank(&self, cell: &Cell<'_, N, Idx>) -> Self::Rank {
+    seeded_hash(self.seed, cell.idx)
+  }
+}
@@ -0,0 +1,16 @@
+use super::{Cell, CellSelector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Selects cells in order of their index, i.e. row by row for cartesian grids
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Scanline;
+
+impl<const N: usize, Idx: Ord + Clone> CellSelector<N, Idx> for Scanline {
+  type Rank = Reverse<Idx>;
+  fn rank(&self, cell: &Cell<'_, N, Idx>) -> Self::Rank {
+    Reverse(cell.idx.clone())
+  }
+}
@@ -0,0 +1,58 @@
+use super::{Cell, CellSelector};
+use crate::utility::TotalF64;
+#[cfg(feature = "serde")]
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{cmp::Reverse, f64::consts::TAU};
+
+/// Selects cells in a spiral outwards from a seed cell, on cartesian grids.
+///
+/// Cells are selected a square ring at a time, and anticlockwise (over the
+/// first two axes) around the seed within each ring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Spiral<const D: usize> {
+  seed: [usize; D],
+}
+
+impl<const D: usize> Spiral<D> {
+  pub fn new(seed: [usize; D]) -> Self {
+    Self { seed }
+  }
+}
+
+impl<const N: usize, const D: usize> CellSelector<N, [usize; D]> for Spiral<D> {
+  type Rank = (Reverse<usize>, Reverse<TotalF64>);
+  fn rank(&self, cell: &Cell<'_, N, [usize; D]>) -> Self::Rank {
+    let offset: [f64; D] = std::array::from_fn(|i| cell.idx[i] as f64 - self.seed[i] as f64);
+    let ring = (0..D)
+      .map(|i| cell.idx[i].abs_diff(self.seed[i]))
+      .max()
+      .unwrap_or(0);
+
+    let (x, y) = (
+      offset.first().copied().unwrap_or(0.0),
+      offset.get(1).copied().unwrap_or(0.0),
+    );
+    let angle = y.atan2(x).rem_euclid(TAU);
+    (Reverse(ring), Reverse(TotalF64(angle)))
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<const D: usize> Serialize for Spiral<D> {
+  fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+    // saved as a slice, the same as a `Domain`'s counts
+    self.seed.as_slice().serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const D: usize> Deserialize<'de> for Spiral<D> {
+  fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    let seed = Vec::<usize>::deserialize(deserializer)?;
+    let len = seed.len();
+    let seed = seed
+      .try_into()
+      .map_err(|_| De::Error::invalid_length(len, &"an index for each axis"))?;
+    Ok(Self { seed })
+  }
+}
@@ -11,7 +11,7 @@ impl<T, D: Dimension> From<Array<T, D>> for ImageGrid<T, D> {
 }
 
 impl<T, D: Dimension> ImageGrid<T, D> {
-  fn overlap(&self, side: &ImageSide) -> Option<ArrayView<T, D>> {
+  fn overlap(&self, side: &ImageSide) -> Option<ArrayView<'_, T, D>> {
     for i in 0..self.0.ndim() {
       match side {
         ImageSide(Axis(j), ImageEnd::Low) if &i == j => {
@@ -33,6 +33,6 @@ impl<T: PartialEq, D: Dimension> Tileable<ImageSide> for ImageGrid<T, D> {
     self
       .overlap(side)
       .zip(other.overlap(&side.opposite()))
-      .map_or(false, |(side0, side1)| side0 == side1)
+      .is_some_and(|(side0, side1)| side0 == side1)
   }
 }
@@ -1,6 +1,7 @@
 pub use crate::{
   grid::Grid,
-  sampling::Sampler,
-  search::{Search, State},
+  sampling::{CellSampler, Predicate, Sampler},
+  search::{Canonical, Evented, Scored, Search, State, Stepwise, WfcObserver},
+  selection::{CellSelector, ValueOrder},
   tiles::{Direction, Tileable},
 };
@@ -4,3 +4,7 @@ mod constructors;
 pub use constructors::{FromFnCount, FromShapeClone, FromShapeDefault, FromShapeFn};
 mod iters;
 pub use iters::IterExtra;
+mod total_f64;
+pub use total_f64::TotalF64;
+mod stable_hasher;
+pub use stable_hasher::StableHasher;
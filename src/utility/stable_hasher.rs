@@ -0,0 +1,58 @@
+use std::hash::Hasher;
+
+/// The multiplier used by FxHash, derived from the golden ratio
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// A fast, non-cryptographic hasher whose algorithm is fixed (FxHash, as used
+/// within rustc), so that hashes are the same from build to build.<br>
+/// Unlike `DefaultHasher`, whose algorithm may change between Rust releases.
+///
+/// @note integers are hashed by value, but slices of integers are hashed by
+/// their bytes in native order, so hashes only match across platforms with the
+/// same pointer width and endianness
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StableHasher {
+  hash: u64,
+}
+
+impl StableHasher {
+  fn add(&mut self, word: u64) {
+    self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+  }
+}
+
+impl Hasher for StableHasher {
+  fn write(&mut self, bytes: &[u8]) {
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+      self.add(u64::from_ne_bytes(chunk.try_into().unwrap()));
+    }
+    for &byte in chunks.remainder() {
+      self.add(byte as u64);
+    }
+  }
+
+  fn write_u8(&mut self, i: u8) {
+    self.add(i as u64);
+  }
+
+  fn write_u16(&mut self, i: u16) {
+    self.add(i as u64);
+  }
+
+  fn write_u32(&mut self, i: u32) {
+    self.add(i as u64);
+  }
+
+  fn write_u64(&mut self, i: u64) {
+    self.add(i);
+  }
+
+  fn write_usize(&mut self, i: usize) {
+    self.add(i as u64);
+  }
+
+  fn finish(&self) -> u64 {
+    self.hash
+  }
+}
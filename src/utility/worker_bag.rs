@@ -2,7 +2,10 @@ use std::{
   collections::TryReserveError,
   hash::{Hash, Hasher},
   ops::Deref,
-  sync::RwLock,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    RwLock,
+  },
 };
 
 use rayon::join;
@@ -35,6 +38,7 @@ type WorkerBagResult<E> = Result<(), WorkerBagError<E>>;
 pub struct WorkerBag<T> {
   failed: RwLock<bool>,
   tasks: RwLock<Vec<T>>,
+  completed: AtomicUsize,
 }
 
 const JUSTIFICATION: &str = r#"
@@ -48,6 +52,7 @@ impl<T: Clone> Clone for WorkerBag<T> {
     Self {
       failed: RwLock::new(*self.failed.read().unwrap()),
       tasks: RwLock::new(self.tasks.read().unwrap().clone()),
+      completed: AtomicUsize::new(self.completed()),
     }
   }
 }
@@ -57,6 +62,7 @@ impl<T> Default for WorkerBag<T> {
     Self {
       failed: RwLock::new(false),
       tasks: RwLock::new(Vec::new()),
+      completed: AtomicUsize::new(0),
     }
   }
 }
@@ -68,13 +74,13 @@ impl<T: PartialEq> PartialEq<WorkerBag<T>> for WorkerBag<T> {
       .read()
       .ok()
       .zip(other.failed.read().ok())
-      .map_or(false, |(failed0, failed1)| failed0.eq(&failed1)))
+      .is_some_and(|(failed0, failed1)| failed0.eq(&failed1)))
       && (self
         .tasks
         .read()
         .ok()
         .zip(other.tasks.read().ok())
-        .map_or(false, |(tasks0, tasks1)| tasks0.eq(tasks1.deref())))
+        .is_some_and(|(tasks0, tasks1)| tasks0.eq(tasks1.deref())))
   }
 }
 impl<T: PartialEq> Eq for WorkerBag<T> {}
@@ -87,6 +93,11 @@ impl<T: Hash> Hash for WorkerBag<T> {
 }
 
 impl<T> WorkerBag<T> {
+  /// The number of tasks the bag has successfully run over its lifetime.
+  pub fn completed(&self) -> usize {
+    self.completed.load(Ordering::Relaxed)
+  }
+
   /// Attempts to add an iterable of tasks to the pending tasks.
   fn try_add_tasks<E>(&self, tasks: impl IntoIterator<Item = T>) -> WorkerBagResult<E> {
     let mut all_tasks = self.tasks.write().expect(JUSTIFICATION);
@@ -105,12 +116,16 @@ impl<T> WorkerBag<T> {
   where
     R: IntoIterator<Item = T>,
   {
-    self.try_add_tasks(worker(task).map_err(WorkerBagError::WorkerError)?)
+    let new_tasks = worker(task).map_err(WorkerBagError::WorkerError)?;
+    self.completed.fetch_add(1, Ordering::Relaxed);
+    self.try_add_tasks(new_tasks)
   }
 }
 
 impl<T: Send + Sync> WorkerBag<T> {
-  fn try_run_tasks<E, R>(&self, worker: impl Fn(T) -> Result<R, E> + Sync) -> WorkerBagResult<E>
+  /// @note the worker is taken by reference, as passing `&worker` on at each
+  /// level of recursion would otherwise produce an infinitely nested type.
+  fn try_run_tasks<E, R>(&self, worker: &(impl Fn(T) -> Result<R, E> + Sync)) -> WorkerBagResult<E>
   where
     R: IntoIterator<Item = T>,
     E: Send,
@@ -121,19 +136,15 @@ impl<T: Send + Sync> WorkerBag<T> {
     }
 
     let task = last.expect("If last is empty, we'll have returned");
-    self.try_run_task(&worker, task).map_err(|err| {
+    self.try_run_task(worker, task).inspect_err(|_| {
       // set the failing tag to ensure all other threads terminate
       let mut failed = self
         .failed
         .write()
         .expect("We won't panic whilst writing true...");
       *failed = true;
-      err
     })?;
-    let results = join(
-      || self.try_run_tasks(&worker),
-      || self.try_run_tasks(&worker),
-    );
+    let results = join(|| self.try_run_tasks(worker), || self.try_run_tasks(worker));
     results.0.and(results.1)
   }
 
@@ -157,7 +168,7 @@ impl<T: Send + Sync> WorkerBag<T> {
     R: IntoIterator<Item = T>,
     E: Send,
   {
-    let result = self.try_run_tasks(worker);
+    let result = self.try_run_tasks(&worker);
     self.reset();
     result
   }
@@ -0,0 +1,113 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::{any, colours};
+use rand_chacha::ChaCha12Rng;
+use std::sync::atomic::Ordering;
+use wfc::{prelude::*, search::Checkpoint, traits::*};
+
+#[test]
+fn restored_searches_continue_identically() {
+  let constraint = colours(3);
+  let grid = Cartesian2([3, 3]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, Uniform::from_seed(0));
+  let mut search = Backtrack::new(state);
+  assert_eq!(search.by_ref().take(5).count(), 5);
+
+  let path = std::env::temp_dir().join(format!("wfc-checkpoint-{}", std::process::id()));
+  // saving over an existing checkpoint replaces it
+  search.save_to(&path).unwrap();
+  search.save_to(&path).unwrap();
+  assert!(!path.with_extension("tmp").exists());
+  let restored = Backtrack::restore_from(&path, (&grid, &constraint)).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  let assignments = |search: Backtrack<_>| -> Vec<_> {
+    search
+      .map(|result| {
+        result
+          .ok()
+          .map(|state: WFCState<_, _, _, _>| state.domains().assignment())
+      })
+      .collect()
+  };
+  let expected = assignments(search);
+  assert!(!expected.is_empty());
+  assert_eq!(assignments(restored), expected);
+}
+
+#[test]
+fn checkpoints_are_saved_part_way_through_a_generation() {
+  // @note every tile fits next to every other, so a single generation takes an
+  // action for every cell without any contradictions
+  let constraint = any(3);
+  let grid = Cartesian2([6, 6]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, Uniform::from_seed(0));
+  let path = std::env::temp_dir().join(format!("wfc-periodic-{}", std::process::id()));
+  let mut search = Backtrack::new(state).checkpoint_every(10, &path);
+  let solution = search.next().unwrap().ok().unwrap();
+  assert!(search.error().is_none());
+  assert_eq!(search.into_inner().stats().decisions, 36);
+
+  let mut restored: Backtrack<WFCState<_, _, _, Uniform<ChaCha12Rng>>> =
+    Backtrack::restore_from(&path, (&grid, &constraint)).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(restored.stats().decisions, 30);
+  let resumed = restored.next().unwrap().ok().unwrap();
+  assert_eq!(
+    resumed.domains().assignment(),
+    solution.domains().assignment()
+  );
+  assert_eq!(restored.stats().decisions, 36);
+}
+
+#[test]
+fn checkpoints_can_be_requested_whilst_searching() {
+  let constraint = any(3);
+  let grid = Cartesian2([4, 4]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, Uniform::from_seed(0));
+  let path = std::env::temp_dir().join(format!("wfc-requested-{}", std::process::id()));
+  let mut search = Naive::new(state).checkpoint_every(0, &path);
+  search.save_requests().store(true, Ordering::Relaxed);
+  assert!(search.next().unwrap().is_ok());
+
+  let restored: Naive<WFCState<_, _, _, Uniform<ChaCha12Rng>>> =
+    Naive::restore_from(&path, (&grid, &constraint)).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(restored.stats().decisions, 1);
+}
+
+#[test]
+fn learnt_weights_are_saved_with_their_selector() {
+  // @note each row is an odd cycle, so it can't be 2 coloured
+  let constraint = colours(2);
+  let grid = Wrapped2([3, 4]);
+  let selector = DomWdeg::new();
+  let state =
+    WFCState::new(grid.cells(), &grid, &constraint, First).with_selector(selector.clone());
+  assert!(Restart::new(state.clone()).next().unwrap().is_err());
+  assert_eq!(selector.wipeouts(), 1);
+
+  let bytes = bincode::serialize(&selector).unwrap();
+  let restored: DomWdeg<[usize; 2]> = bincode::deserialize(&bytes).unwrap();
+  assert_eq!(restored.wipeouts(), 1);
+  for idx in grid.cells() {
+    for side in 0..4 {
+      assert_eq!(restored.weight(idx, side), selector.weight(idx, side));
+    }
+  }
+
+  // the best partial state shares its weights with the state the search
+  // restarts from again, so it sees what later attempts learn
+  let mut search = Restart::new(state);
+  assert!(search.next().unwrap().is_err());
+  let path = std::env::temp_dir().join(format!("wfc-dom-wdeg-{}", std::process::id()));
+  search.save_to(&path).unwrap();
+  let mut restored: Restart<WFCState<_, _, _, First, DomWdeg<_>>> =
+    Restart::restore_from(&path, (&grid, &constraint)).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert!(restored.next().unwrap().is_err());
+  let best = restored.best_partial().unwrap();
+  assert_eq!(best.selector().wipeouts(), 3);
+}
@@ -0,0 +1,44 @@
+//! Tiles shared between the integration tests
+#![allow(dead_code)]
+
+use wfc::{consistency::Constraint, traits::*};
+
+/// A side for tiles that don't care which way round they're placed
+#[derive(Clone, Copy)]
+pub struct Side;
+
+impl Direction for Side {
+  fn opposite(&self) -> Self {
+    Side
+  }
+}
+
+/// A tile that can be placed next to any other tile
+pub struct Any;
+
+impl Tileable<Side> for Any {
+  fn tiles(&self, _: &Self, _: &Side) -> bool {
+    true
+  }
+}
+
+/// A tile that can be placed next to any tile of a different colour
+pub struct Colour(pub usize);
+
+impl Tileable<Side> for Colour {
+  fn tiles(&self, other: &Self, _: &Side) -> bool {
+    self.0 != other.0
+  }
+}
+
+/// A constraint on a 2D grid where every tile fits next to every other tile
+pub fn any(no_tiles: usize) -> Constraint<4> {
+  let tiles: Vec<_> = (0..no_tiles).map(|_| Any).collect();
+  Constraint::new(&tiles, &[Side; 4])
+}
+
+/// A constraint on a 2D grid where neighbouring cells must differ in colour
+pub fn colours(no_colours: usize) -> Constraint<4> {
+  let tiles: Vec<_> = (0..no_colours).map(Colour).collect();
+  Constraint::new(&tiles, &[Side; 4])
+}
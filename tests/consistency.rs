@@ -0,0 +1,125 @@
+use wfc::{
+  consistency::{ac3, AC3ErrorKind, CSPDomains, Constraint, Domain, Propagation, PropagationError},
+  prelude::*,
+};
+
+/// The sides of a cell in a 2D cartesian grid, in the grid's order
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Dir {
+  Left,
+  Down,
+  Right,
+  Up,
+}
+
+impl Direction for Dir {
+  fn opposite(&self) -> Self {
+    match self {
+      Dir::Left => Dir::Right,
+      Dir::Down => Dir::Up,
+      Dir::Right => Dir::Left,
+      Dir::Up => Dir::Down,
+    }
+  }
+}
+
+/// A tile that must be followed by the next step to its right, counting
+/// around in a cycle of 4 steps
+struct Step(usize);
+
+impl Tileable<Dir> for Step {
+  fn tiles(&self, other: &Self, side: &Dir) -> bool {
+    match side {
+      Dir::Left => (other.0 + 1) % 4 == self.0,
+      Dir::Right => other.0 == (self.0 + 1) % 4,
+      Dir::Down | Dir::Up => true,
+    }
+  }
+}
+
+fn steps() -> Constraint<4> {
+  let tiles: Vec<_> = (0..4).map(Step).collect();
+  Constraint::new(&tiles, &[Dir::Left, Dir::Down, Dir::Right, Dir::Up])
+}
+
+type Idx = [usize; 2];
+
+/// Collapses the cell at `start` to `tile` on a grid where every cell starts
+/// with every tile, propagating the choice to the other cells
+fn choose(
+  grid: &Wrapped2,
+  start: Idx,
+  tile: usize,
+) -> Result<(CSPDomains<4, Idx>, Propagation<Idx>), PropagationError<Idx>> {
+  let constraint = steps();
+  let domain = Domain::constraint(&constraint, 4);
+  let domains = grid
+    .cells()
+    .into_iter()
+    .map(|idx| (idx, domain.clone()))
+    .collect();
+  ac3(domains, 4, grid, &constraint, &start, tile)
+}
+
+#[test]
+fn propagation_follows_the_direction_of_the_constraint() {
+  let grid = Wrapped2([4, 1]);
+  for (start, tile) in [([0, 0], 0), ([1, 0], 1), ([3, 0], 3)] {
+    let Ok((domains, propagation)) = choose(&grid, start, tile) else {
+      panic!("{start:?} can be {tile}");
+    };
+    assert_eq!(
+      domains.assignment().unwrap(),
+      [([0, 0], 0), ([1, 0], 1), ([2, 0], 2), ([3, 0], 3)]
+    );
+    assert_eq!(propagation.changed.len(), 4);
+  }
+}
+
+#[test]
+fn propagation_fails_when_a_choice_is_inconsistent() {
+  // 4 steps can't be wrapped around 3 cells
+  let Err(error) = choose(&Wrapped2([3, 1]), [0, 0], 0) else {
+    panic!("a cycle of 3 cells can't be filled");
+  };
+  assert_eq!(error.kind, AC3ErrorKind::InconsistentChoice);
+  assert!(error.wipeout.is_some());
+}
+
+/// The entropy of the weighted tiles, calculated from scratch
+fn entropy(weights: &[f64], tiles: impl Iterator<Item = usize>) -> f64 {
+  let weights: Vec<_> = tiles.map(|tile| weights[tile]).collect();
+  let sum: f64 = weights.iter().sum();
+  sum.ln() - weights.iter().map(|w| w * w.ln()).sum::<f64>() / sum
+}
+
+#[test]
+fn entropy_is_kept_up_to_date_as_tiles_are_removed() {
+  let constraint = steps();
+  let weights = [1.0, 2.0, 0.5, 4.0];
+  let mut domain = Domain::constraint(&constraint, 4);
+  domain.set_weights(weights.into());
+  assert!((domain.entropy().unwrap() - entropy(&weights, 0..4)).abs() < 1e-9);
+
+  // each step only has one tile to its left, so removing that tile's support
+  // removes the step
+  let mut removed = vec![];
+  for (tile, by_side) in [(2, false), (0, true), (3, false)] {
+    match by_side {
+      true => assert!(domain.remove_side(tile, 0)),
+      false => assert!(domain.remove_item(tile)),
+    }
+    removed.push(tile);
+
+    let mut scratch = Domain::constraint(&constraint, 4);
+    removed.iter().for_each(|&tile| {
+      scratch.remove_item(tile);
+    });
+    scratch.set_weights(weights.into());
+
+    let expected = entropy(&weights, domain.iter());
+    assert!((domain.entropy().unwrap() - expected).abs() < 1e-9);
+    assert!((scratch.entropy().unwrap() - expected).abs() < 1e-9);
+  }
+  assert!(domain.entropy().unwrap().abs() < 1e-9);
+}
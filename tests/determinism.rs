@@ -0,0 +1,97 @@
+mod common;
+
+use common::colours;
+use std::hash::{Hash, Hasher};
+use wfc::{prelude::*, traits::*, utility::StableHasher};
+
+/// Generates a map on a wrapped grid, returning the tile assigned to each cell
+fn generate<S, C>(sampler: S, selector: C) -> Vec<([usize; 2], usize)>
+where
+  S: CellSampler<4, [usize; 2]> + Clone,
+  C: CellSelector<4, [usize; 2]> + Clone,
+{
+  let constraint = colours(4);
+  let grid = Wrapped2([8, 8]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler).with_selector(selector);
+  let solution = Backtrack::new(state)
+    .find_map(Result::ok)
+    .expect("a 4 colouring of an even wrapped grid exists");
+  solution.domains().assignment().unwrap()
+}
+
+#[test]
+fn default_selection_is_reproducible() {
+  assert_eq!(generate(First, MrvDegree), generate(First, MrvDegree));
+}
+
+#[test]
+fn random_order_is_reproducible_from_a_seed() {
+  assert_eq!(
+    generate(First, RandomOrder::new(7)),
+    generate(First, RandomOrder::new(7))
+  );
+  assert_ne!(
+    generate(First, RandomOrder::new(7)),
+    generate(First, RandomOrder::new(8))
+  );
+}
+
+#[test]
+fn entropy_noise_is_reproducible_from_a_seed() {
+  let entropy = |seed| Entropy::new(vec![1.0, 2.0, 3.0, 4.0]).with_seed(seed);
+  assert_eq!(generate(First, entropy(3)), generate(First, entropy(3)));
+}
+
+#[test]
+fn uniform_sampling_is_reproducible_from_a_seed() {
+  let uniform = Uniform::from_seed;
+  assert_eq!(
+    generate(uniform(1), MrvDegree),
+    generate(uniform(1), MrvDegree)
+  );
+  assert_ne!(
+    generate(uniform(1), MrvDegree),
+    generate(uniform(2), MrvDegree)
+  );
+}
+
+#[test]
+fn weighted_sampling_is_reproducible_from_a_seed() {
+  let weights = [1.0, 2.0, 3.0, 4.0];
+  let weighted = |seed| Weighted::from_seed(seed, weights).unwrap();
+  assert_eq!(
+    generate(weighted(1), MrvDegree),
+    generate(weighted(1), MrvDegree)
+  );
+  assert_ne!(
+    generate(weighted(1), MrvDegree),
+    generate(weighted(2), MrvDegree)
+  );
+}
+
+#[test]
+fn events_are_reproducible() {
+  let constraint = colours(4);
+  let grid = Wrapped2([6, 6]);
+  let events = || {
+    let state = WFCState::new(grid.cells(), &grid, &constraint, First);
+    Steps::new(state).collect::<Vec<_>>()
+  };
+  assert_eq!(events(), events());
+}
+
+/// Within one process any hasher gives the same results every time, so these
+/// are checked against values from an earlier build to catch hashes changing
+/// between builds
+#[test]
+fn hashes_are_the_same_in_every_build() {
+  let mut hasher = StableHasher::default();
+  [3usize, 4].hash(&mut hasher);
+  assert_eq!(hasher.finish(), 0xbe29d30c805870c9);
+
+  let row: Vec<_> = (generate(First, RandomOrder::new(7)).into_iter())
+    .filter(|&([x, _], _)| x == 0)
+    .map(|(_, tile)| tile)
+    .collect();
+  assert_eq!(row, [3, 2, 3, 1, 0, 1, 0, 1]);
+}
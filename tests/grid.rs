@@ -0,0 +1,39 @@
+use wfc::prelude::*;
+
+#[test]
+fn cartesian_neighbours_stop_at_the_edges() {
+  let grid = Cartesian2([3, 3]);
+  assert_eq!(
+    grid.neighbours(&[0, 0]),
+    [None, None, Some([1, 0]), Some([0, 1])]
+  );
+  assert_eq!(
+    grid.neighbours(&[1, 1]),
+    [Some([0, 1]), Some([1, 0]), Some([2, 1]), Some([1, 2])]
+  );
+  assert_eq!(
+    grid.neighbours(&[2, 1]),
+    [Some([1, 1]), Some([2, 0]), None, Some([2, 2])]
+  );
+}
+
+#[test]
+fn wrapped_neighbours_wrap_around_the_edges() {
+  let grid = Wrapped2([3, 3]);
+  assert_eq!(
+    grid.neighbours(&[0, 1]),
+    [Some([2, 1]), Some([0, 0]), Some([1, 1]), Some([0, 2])]
+  );
+}
+
+#[test]
+fn neighbours_face_back_on_the_opposite_side() {
+  let grid = Cartesian2([3, 4]);
+  for idx in grid.cells() {
+    for (side, neighbour) in grid.neighbours(&idx).into_iter().enumerate() {
+      if let Some(neighbour) = neighbour {
+        assert_eq!(grid.neighbours(&neighbour)[grid.opposite(side)], Some(idx));
+      }
+    }
+  }
+}
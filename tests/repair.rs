@@ -0,0 +1,124 @@
+mod common;
+
+use common::colours;
+use std::collections::HashMap;
+use wfc::prelude::*;
+
+#[test]
+fn repair_converges_on_a_valid_assignment() {
+  let constraint = colours(2);
+  let grid = Cartesian2([1, 6]);
+  // every neighbour conflicts, and the sampler only has weights for 2 tiles
+  // (rather than for each of the 6 cells)
+  let assignment = grid.cells().into_iter().map(|idx| (idx, 0));
+  let sampler = Weighted::from_seed(0, [1.0, 1.0]).unwrap();
+  let Ok(mut repair) = MinConflicts::new(assignment, &grid, &constraint, sampler) else {
+    panic!("every tile is in the constraint");
+  };
+
+  assert_eq!(repair.repair(100), Ok(true));
+  assert!(repair.is_valid());
+  let tiles: Vec<_> = repair.assignment().iter().map(|(_, tile)| *tile).collect();
+  assert!(tiles == [0, 1, 0, 1, 0, 1] || tiles == [1, 0, 1, 0, 1, 0]);
+}
+
+#[test]
+fn repair_converges_from_any_seed() {
+  let constraint = colours(3);
+  let grid = Cartesian2([4, 4]);
+  for seed in 0..8 {
+    let assignment = grid.cells().into_iter().map(|idx| (idx, 0));
+    let Ok(repair) = MinConflicts::new(assignment, &grid, &constraint, Uniform::from_seed(seed))
+    else {
+      panic!("every tile is in the constraint");
+    };
+    let mut repair = repair.with_seed(seed);
+    assert_eq!(repair.repair(1000), Ok(true), "seed {seed} didn't converge");
+  }
+}
+
+#[test]
+fn tiles_outside_the_constraint_are_rejected() {
+  let constraint = colours(2);
+  let grid = Cartesian2([1, 3]);
+  let assignment = [([0, 0], 0), ([0, 1], 2), ([0, 2], 1)];
+  assert!(matches!(
+    MinConflicts::new(assignment, &grid, &constraint, First),
+    Err(RepairError::UnknownTile([0, 1], 2))
+  ));
+}
+
+#[test]
+fn repair_samples_with_the_cell_being_reassigned() {
+  // either cell can be reassigned to tiles 1 or 2, and the filter prefers a
+  // different tile for each
+  let constraint = colours(3);
+  let grid = Cartesian2([1, 2]);
+  let assignment = grid.cells().into_iter().map(|idx| (idx, 0));
+  let sampler = Filter::new(|&[_, y]: &[usize; 2], tile| tile == y + 1, First);
+  let Ok(mut repair) = MinConflicts::new(assignment, &grid, &constraint, sampler) else {
+    panic!("every tile is in the constraint");
+  };
+
+  assert_eq!(repair.repair(1), Ok(true));
+  let tiles: Vec<_> = repair.assignment().iter().map(|(_, tile)| *tile).collect();
+  assert!(tiles == [1, 0] || tiles == [0, 2], "{tiles:?}");
+}
+
+#[test]
+fn repair_accepts_combined_samplers() {
+  let constraint = colours(3);
+  let grid = Cartesian2([4, 4]);
+  let assignment = grid.cells().into_iter().map(|idx| (idx, 0));
+  let sampler = Mix::from_seed(0, First, Uniform::from_seed(0), 0.5);
+  let Ok(mut repair) = MinConflicts::new(assignment, &grid, &constraint, sampler) else {
+    panic!("every tile is in the constraint");
+  };
+  assert_eq!(repair.repair(1000), Ok(true));
+}
+
+#[test]
+fn repair_keeps_fixed_tiles_that_would_still_conflict() {
+  // reassigning `[0, 0]` to tile 1 would remove its conflict, but it's fixed
+  let constraint = colours(2);
+  let grid = Cartesian2([1, 2]);
+  let assignment = grid.cells().into_iter().map(|idx| (idx, 0));
+  let sampler = Fixed::new([([0, 0], 0)], First);
+  let Ok(mut repair) = MinConflicts::new(assignment, &grid, &constraint, sampler) else {
+    panic!("every tile is in the constraint");
+  };
+
+  assert_eq!(repair.repair(100), Ok(true));
+  assert_eq!(repair.assignment(), &[([0, 0], 0), ([0, 1], 1)]);
+}
+
+#[test]
+fn conflicts_are_counted_as_cells_are_reassigned() {
+  let constraint = colours(3);
+  let grid = Cartesian2([4, 4]);
+  let assignment = grid.cells().into_iter().map(|idx| (idx, 0));
+  let Ok(mut repair) = MinConflicts::new(assignment, &grid, &constraint, Uniform::from_seed(0))
+  else {
+    panic!("every tile is in the constraint");
+  };
+
+  // counts the cells that conflict with a neighbour from scratch
+  let conflicted = |assignment: &[([usize; 2], usize)]| {
+    let tiles: HashMap<_, _> = assignment.iter().copied().collect();
+    (assignment.iter())
+      .filter(|(idx, tile)| {
+        (grid.neighbours(idx).into_iter().enumerate()).any(|(side, optn)| {
+          optn.is_some_and(|neighbour| !constraint[(*tile, tiles[&neighbour], side)])
+        })
+      })
+      .count()
+  };
+  loop {
+    let left = repair.step().unwrap();
+    assert_eq!(left, conflicted(repair.assignment()));
+    assert_eq!(repair.is_valid(), left == 0);
+    if left == 0 {
+      break;
+    }
+  }
+}
@@ -0,0 +1,511 @@
+mod common;
+
+use common::{any, colours, Side};
+use rand::{rngs::StdRng, SeedableRng};
+use wfc::{
+  consistency::{Constraint, Domain},
+  prelude::*,
+  selection::Cell,
+  traits::*,
+};
+
+#[test]
+fn invalid_weights_are_rejected() {
+  assert_eq!(
+    Weighted::from_seed(0, [1.0, -1.0]),
+    Err(WeightError::Negative(1))
+  );
+  assert_eq!(
+    Weighted::from_seed(0, [f64::NAN]),
+    Err(WeightError::NotFinite(0))
+  );
+  assert_eq!(
+    Weighted::from_seed(0, [0.0, 0.0]),
+    Err(WeightError::NoPositive)
+  );
+}
+
+#[test]
+fn weights_can_be_named_and_updated() {
+  let names = ["grass", "water", "sand"];
+  let mut weighted = Weighted::from_names(
+    StdRng::seed_from_u64(0),
+    &names,
+    [("grass", 3.0), ("sand", 1.0)],
+  )
+  .unwrap();
+  assert_eq!(&weighted.weights()[..], &[3.0, 0.0, 1.0]);
+  // tiles without a name are never picked, even when they're all that's left
+  assert_eq!(weighted.sample(&[1]), Err(SampleError::ZeroWeight));
+
+  let shared = weighted.weights().clone();
+  weighted.set_weight(1, 2.0).unwrap();
+  assert_eq!(&weighted.weights()[..], &[3.0, 2.0, 1.0]);
+  assert_eq!(&shared[..], &[3.0, 0.0, 1.0]);
+
+  assert_eq!(
+    weighted.set_weight(0, f64::INFINITY),
+    Err(WeightError::NotFinite(0))
+  );
+  assert_eq!(
+    Weighted::from_names(StdRng::seed_from_u64(0), &names, [("lava", 1.0)]),
+    Err(WeightError::UnknownName("lava".to_string()))
+  );
+}
+
+#[test]
+fn positional_weights_depend_on_the_cell() {
+  let constraint = any(2);
+  let grid = Cartesian2([8, 8]);
+  let left_or_right = |&[x, _]: &[usize; 2], tile: usize| match (x < 4, tile) {
+    (true, 0) | (false, 1) => 1.0,
+    _ => 0.0,
+  };
+  let sampler = Positional::from_seed(0, left_or_right);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let solution = Backtrack::new(state).find_map(Result::ok).unwrap();
+  for ([x, _], tile) in solution.domains().assignment().unwrap() {
+    assert_eq!(tile, (x >= 4) as usize);
+  }
+}
+
+#[test]
+fn frequency_counts_tiles_once_placed() {
+  let mut frequency = Frequency::from_seed(0, [1.0, 1.0]).unwrap();
+  frequency.sample(&[0]).unwrap();
+  frequency.sample(&[1]).unwrap();
+  assert_eq!(frequency.counts(), &[0, 0]);
+  assert_eq!(frequency.clone().counts(), &[0, 0]);
+
+  Sampler::collapsed(&mut frequency, 1);
+  assert_eq!(frequency.counts(), &[0, 1]);
+}
+
+#[test]
+fn frequency_counts_tiles_forced_by_propagation() {
+  // @note choosing any one cell forces the colour of every other cell
+  let constraint = colours(2);
+  let grid = Cartesian2([4, 4]);
+  let sampler = Frequency::from_seed(0, [1.0, 1.0]).unwrap();
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let mut restart = Restart::new(state);
+  let solution = restart.find_map(Result::ok).unwrap();
+  assert_eq!(solution.sampler().counts(), &[8, 8]);
+}
+
+#[test]
+fn frequency_converges_on_targets() {
+  let constraint = any(2);
+  let grid = Cartesian2([10, 10]);
+  let sampler = Frequency::from_seed(0, [1.0, 9.0]).unwrap();
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let solution = Backtrack::new(state).find_map(Result::ok).unwrap();
+  let assignment = solution.domains().assignment().unwrap();
+  let rare = assignment.iter().filter(|(_, tile)| *tile == 0).count();
+  assert!((9..=11).contains(&rare), "{rare} of 100 tiles were rare");
+  assert_eq!(solution.sampler().counts(), &[rare, 100 - rare]);
+}
+
+#[test]
+fn markov_learns_preferences_from_an_example() {
+  let stripes = Cartesian2([8, 8]);
+  let example = stripes.cells().into_iter().map(|[x, y]| ([x, y], x % 2));
+  let markov = Markov::from_example(StdRng::seed_from_u64(0), 2, &stripes, example).unwrap();
+  let affinity = |tile, neighbour, side| markov.affinity(tile, neighbour, side).unwrap();
+  assert!(affinity(0, 1, 0) > affinity(0, 0, 0));
+  assert!(affinity(0, 0, 1) > affinity(0, 1, 1));
+
+  let constraint = any(2);
+  let grid = Cartesian2([10, 10]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, markov).with_selector(Scanline);
+  let solution = Backtrack::new(state).find_map(Result::ok).unwrap();
+
+  let assignment: std::collections::HashMap<_, _> = solution
+    .domains()
+    .assignment()
+    .unwrap()
+    .into_iter()
+    .collect();
+  let striped = (assignment.iter())
+    .filter(|([x, y], tile)| assignment.get(&[x + 1, *y]).is_some_and(|t| t != *tile))
+    .count();
+  // @note without any affinities, around half of the pairs would be striped
+  assert!(
+    striped > 60,
+    "{striped} of 90 horizontal pairs were striped"
+  );
+}
+
+#[test]
+fn markov_rejects_unknown_tiles() {
+  let grid = Cartesian2([2, 1]);
+  let example = [([0, 0], 0), ([1, 0], 2)];
+  assert_eq!(
+    Markov::from_example(StdRng::seed_from_u64(0), 2, &grid, example).err(),
+    Some(WeightError::UnknownTile(2))
+  );
+
+  let mut markov = Markov::<4, _>::from_seed(0, [1.0, 1.0]).unwrap();
+  assert_eq!(
+    markov.learn(&grid, example),
+    Err(WeightError::UnknownTile(2))
+  );
+  assert_eq!(markov.affinity(0, 1, 0), Ok(1.0));
+  assert_eq!(markov.affinity(0, 2, 0), Err(WeightError::UnknownTile(2)));
+  assert_eq!(markov.affinity(0, 1, 4), Err(WeightError::UnknownSide(4)));
+
+  assert_eq!(
+    markov.set_affinity(2, 0, 0, 1.0),
+    Err(WeightError::UnknownTile(2))
+  );
+  assert_eq!(
+    markov.set_affinity(0, 2, 0, 1.0),
+    Err(WeightError::UnknownTile(2))
+  );
+  assert_eq!(
+    markov.set_affinity(0, 1, 4, 1.0),
+    Err(WeightError::UnknownSide(4))
+  );
+}
+
+#[test]
+fn markov_fails_next_to_tiles_it_doesnt_weight() {
+  let constraint = any(4);
+  let grid = Cartesian2([1, 2]);
+  let markov = Markov::from_seed(0, [1.0; 3]).unwrap();
+  let sampler = Filter::new(|_: &[usize; 2], tile| tile < 3, markov);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let mut state = state.take_action(&([0, 1], 3)).unwrap();
+  let actions = state.get_actions();
+  assert_eq!(
+    state.pick_action(actions.iter()),
+    Err(PickActionError::Sample(SampleError::MissingWeight(3)))
+  );
+}
+
+#[test]
+fn sampling_fails_without_a_valid_entry() {
+  assert_eq!(First.sample(&[]), Err(SampleError::Empty));
+  assert_eq!(Uniform::from_seed(0).sample(&[]), Err(SampleError::Empty));
+
+  let mut weighted = Weighted::from_seed(0, [1.0, 0.0]).unwrap();
+  assert_eq!(weighted.sample(&[1]), Err(SampleError::ZeroWeight));
+  assert_eq!(weighted.sample(&[0, 2]), Err(SampleError::MissingWeight(2)));
+  assert_eq!(weighted.sample(&[0, 1]), Ok(0));
+}
+
+#[test]
+fn sampling_errors_are_returned_when_picking() {
+  let constraint = any(2);
+  let grid = Cartesian2([2, 2]);
+  let sampler = Weighted::from_seed(0, [1.0]).unwrap();
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let actions = state.get_actions();
+  assert_eq!(
+    state.pick_action(actions.iter().filter(|(_, tile)| *tile == 1)),
+    Err(PickActionError::Sample(SampleError::MissingWeight(1)))
+  );
+}
+
+#[test]
+fn weighted_domains_find_items_by_cumulative_weight() {
+  let constraint = any(4);
+  let mut domain = Domain::constraint(&constraint, 4);
+  domain.set_sampling_weights(vec![1.0, 2.0, 3.0, 4.0].into());
+  domain.remove_item(1);
+
+  assert_eq!(domain.total_weight(), Some(8.0));
+  assert_eq!(domain.item_at_weight(0.5), Some(0));
+  assert_eq!(domain.item_at_weight(1.0), Some(2));
+  assert_eq!(domain.item_at_weight(3.9), Some(2));
+  assert_eq!(domain.item_at_weight(4.0), Some(3));
+  assert_eq!(domain.item_at_weight(7.9), Some(3));
+  assert_eq!(domain.item_at_weight(8.0), None);
+}
+
+#[test]
+fn cached_weighted_sampling_follows_the_weights() {
+  let constraint = any(4);
+  let grid = Cartesian2([1, 1]);
+  let sampler = CachedWeighted::from_seed(0, [1.0, 2.0, 3.0, 4.0]).unwrap();
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+  let actions = state.get_actions();
+
+  // every tile left is sampled from the domain, any fewer are sampled directly
+  for (tiles, expected) in [
+    (0..4, [0.1, 0.2, 0.3, 0.4]),
+    (2..4, [0.0, 0.0, 3.0 / 7.0, 4.0 / 7.0]),
+  ] {
+    let mut counts = [0; 4];
+    for _ in 0..10_000 {
+      let picked = actions.iter().filter(|(_, tile)| tiles.contains(tile));
+      let Ok((_, tile)) = state.pick_action(picked) else {
+        panic!("a tile should be picked");
+      };
+      counts[tile] += 1;
+    }
+    for (count, expected) in counts.into_iter().zip(expected) {
+      assert!((count as f64 / 10_000.0 - expected).abs() < 0.02);
+    }
+  }
+}
+
+#[test]
+fn samplers_and_selectors_weight_domains_separately() {
+  let constraint = any(4);
+  let mut domain = Domain::constraint(&constraint, 4);
+  domain.set_weights(vec![4.0, 3.0, 2.0, 1.0].into());
+  assert_eq!(domain.sampling_weights(), None);
+  assert_eq!(domain.total_weight(), None);
+
+  let grid = Cartesian2([1, 1]);
+  let sampler = CachedWeighted::from_seed(0, [1.0, 2.0, 3.0, 4.0]).unwrap();
+  let selector = Entropy::new(vec![4.0, 3.0, 2.0, 1.0]);
+  let mut state =
+    WFCState::new(grid.cells(), &grid, &constraint, sampler.clone()).with_selector(selector);
+  let (weights, sampling) = (state.domains())
+    .read_at(&[0, 0], |d| {
+      (d.weights().cloned(), d.sampling_weights().cloned())
+    })
+    .unwrap();
+  assert_eq!(weights.as_deref(), Some(&[4.0, 3.0, 2.0, 1.0][..]));
+  assert_eq!(sampling.as_ref(), Some(sampler.weights()));
+
+  let actions = state.get_actions();
+  let mut counts = [0; 4];
+  for _ in 0..10_000 {
+    let Ok((_, tile)) = state.pick_action(actions.iter()) else {
+      panic!("a tile should be picked");
+    };
+    counts[tile] += 1;
+  }
+  for (count, expected) in counts.into_iter().zip([0.1, 0.2, 0.3, 0.4]) {
+    assert!((count as f64 / 10_000.0 - expected).abs() < 0.02);
+  }
+}
+
+#[test]
+fn mixed_samplers_keep_the_distribution_of_each_sampler() {
+  // the second sampler's weights are cached within the domain, so the first has
+  // to sample without its cache
+  let constraint = any(4);
+  let grid = Cartesian2([1, 1]);
+  let first = CachedWeighted::from_seed(0, [1.0, 2.0, 3.0, 4.0]).unwrap();
+  let second = CachedWeighted::from_seed(0, [4.0, 3.0, 2.0, 1.0]).unwrap();
+  let sampler = Mix::from_seed(0, first, second, 1.0);
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let actions = state.get_actions();
+  let mut counts = [0; 4];
+  for _ in 0..10_000 {
+    let Ok((_, tile)) = state.pick_action(actions.iter()) else {
+      panic!("a tile should be picked");
+    };
+    counts[tile] += 1;
+  }
+  for (count, expected) in counts.into_iter().zip([0.1, 0.2, 0.3, 0.4]) {
+    assert!((count as f64 / 10_000.0 - expected).abs() < 0.02);
+  }
+}
+
+#[test]
+fn samplers_without_a_cell_are_samplers() {
+  let coldest = Temperature::from_seed(0, [1.0, 2.0, 3.0], 0.0).unwrap();
+  let mut mix = Mix::from_seed(0, First, coldest.clone(), 1.0);
+  assert_eq!(mix.sample(&[1, 2]), Ok(1));
+  let mut mix = Mix::from_seed(0, First, coldest, 0.0);
+  assert_eq!(mix.sample(&[1, 2]), Ok(2));
+  assert_eq!(mix.sample(&[]), Err(SampleError::Empty));
+
+  // filters on the tile alone don't need a cell either
+  let mut odd = Filter::by_tile(|tile| tile % 2 == 1, Mix::from_seed(0, First, First, 0.5));
+  assert_eq!(odd.sample(&[0, 2, 3, 5]), Ok(3));
+  assert_eq!(odd.sample(&[0, 2]), Ok(0));
+  assert_eq!(odd.sample(&[]), Err(SampleError::Empty));
+}
+
+#[test]
+fn filters_on_the_tile_alone_plug_into_states() {
+  let constraint = any(3);
+  let grid = Cartesian2([2, 2]);
+  let sampler = Filter::by_tile(|tile| tile == 2, First);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let solution = Backtrack::new(state).find_map(Result::ok).unwrap();
+  let assignment = solution.domains().assignment().unwrap();
+  assert!(assignment.iter().all(|(_, tile)| *tile == 2));
+}
+
+#[test]
+fn temperature_sharpens_or_flattens_weights() {
+  let constraint = any(4);
+  let grid = Cartesian2([1, 1]);
+  let frequencies = |temperature| {
+    let sampler = Temperature::from_seed(0, [1.0, 2.0, 3.0, 4.0], temperature).unwrap();
+    let mut state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+    let actions = state.get_actions();
+    let mut counts = [0; 4];
+    for _ in 0..10_000 {
+      let Ok((_, tile)) = state.pick_action(actions.iter()) else {
+        panic!("a tile should be picked");
+      };
+      counts[tile] += 1;
+    }
+    counts.map(|count| count as f64 / 10_000.0)
+  };
+  let close = |a: [f64; 4], b: [f64; 4]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 0.02);
+
+  assert_eq!(frequencies(0.0), [0.0, 0.0, 0.0, 1.0]);
+  assert!(close(frequencies(1.0), [0.1, 0.2, 0.3, 0.4]));
+  assert!(close(frequencies(1000.0), [0.25; 4]));
+}
+
+#[test]
+fn temperature_follows_its_schedule() {
+  let constraint = any(2);
+  let grid = Cartesian2([8, 8]);
+  // deterministic on the left half, and uniform on the right half
+  let schedule = |&[x, _]: &[usize; 2], _| if x < 4 { 0.0 } else { f64::INFINITY };
+  let sampler = Temperature::from_seed(0, [1.0, 2.0], 1.0)
+    .unwrap()
+    .with_schedule(schedule);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let solution = Backtrack::new(state).find_map(Result::ok).unwrap();
+  let assignment = solution.domains().assignment().unwrap();
+  let left = assignment.iter().filter(|([x, _], _)| *x < 4);
+  assert!(left.clone().all(|(_, tile)| *tile == 1));
+  let right = assignment.iter().filter(|([x, _], _)| *x >= 4);
+  assert!(right.clone().any(|(_, tile)| *tile == 0));
+}
+
+#[test]
+fn combinators_plug_into_states() {
+  let constraint = any(3);
+  let grid = Cartesian2([8, 8]);
+  // the left half picks from tiles 0 and 1, the right half only takes tile 2
+  let mixed = Mix::from_seed(0, First, Uniform::from_seed(0), 0.5);
+  let only = |tiles: fn(&[usize; 2], usize) -> bool| Filter::new(tiles, mixed.clone());
+  let regions = PerRegion::new(|&[x, _]: &[usize; 2]| x < 4, only(|_, tile| tile == 2))
+    .with_sampler(true, only(|_, tile| tile < 2));
+  let sampler = Fixed::new([([0, 0], 2)], regions);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let solution = Backtrack::new(state).find_map(Result::ok).unwrap();
+  for ([x, y], tile) in solution.domains().assignment().unwrap() {
+    match ([x, y], x < 4) {
+      ([0, 0], _) => assert_eq!(tile, 2),
+      (_, true) => assert!(tile < 2),
+      (_, false) => assert_eq!(tile, 2),
+    }
+  }
+}
+
+#[test]
+fn fixed_tiles_that_are_unavailable_fail_to_sample() {
+  let constraint = any(2);
+  let grid = Cartesian2([1, 1]);
+  let sampler = Fixed::new([([0, 0], 1)], First);
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+
+  let actions = state.get_actions();
+  assert_eq!(
+    state.pick_action(actions.iter().filter(|(_, tile)| *tile == 0)),
+    Err(PickActionError::Sample(SampleError::Unavailable(1)))
+  );
+}
+
+#[test]
+fn fixed_tiles_are_kept_when_forced_by_propagation() {
+  // `[0, 0]` is collapsed first, to tile `0`, which forces `[1, 0]` to tile `1`
+  let constraint = colours(2);
+  let grid = Cartesian2([2, 1]);
+  let sampler = Fixed::new([([1, 0], 0)], First);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler).with_selector(Scanline);
+
+  let solutions: Vec<_> = Backtrack::new(state)
+    .filter_map(Result::ok)
+    .map(|solution| solution.domains().assignment().unwrap())
+    .collect();
+  assert_eq!(solutions, vec![vec![([0, 0], 1), ([1, 0], 0)]]);
+}
+
+#[test]
+fn mixed_samplers_keep_fixed_tiles() {
+  let constraint = any(2);
+  let grid = Cartesian2([1, 1]);
+  // the tile is fixed whichever sampler is picked
+  for p in [0.0, 1.0] {
+    let sampler = Mix::from_seed(0, First, Fixed::new([([0, 0], 1)], First), p);
+    let mut state = WFCState::new(grid.cells(), &grid, &constraint, sampler);
+    let actions = state.get_actions();
+    assert!(matches!(state.pick_action(actions.iter()), Ok(([0, 0], 1))));
+  }
+}
+
+/// Tile `0` fits next to anything, tile `1` only fits next to tile `0`
+struct Shy(usize);
+
+impl Tileable<Side> for Shy {
+  fn tiles(&self, other: &Self, _: &Side) -> bool {
+    self.0 == 0 || other.0 == 0
+  }
+}
+
+#[test]
+fn fixed_tiles_are_picked_before_values_are_ordered() {
+  let constraint = Constraint::new(&[Shy(0), Shy(1)], &[Side; 4]);
+  let grid = Cartesian2([3, 1]);
+  // @note the least constraining value is always tile `0`, so ordering values
+  // would narrow the fixed tile away
+  let solve = |order| {
+    let sampler = Fixed::new([([1, 0], 1)], First);
+    let state = WFCState::new(grid.cells(), &grid, &constraint, sampler)
+      .with_value_order(LeastConstraining::new(order));
+    Backtrack::new(state).find_map(Result::ok).unwrap()
+  };
+
+  for order in [Combine::Tiebreak, Combine::Weighting] {
+    let assignment = solve(order).domains().assignment().unwrap();
+    assert!(assignment.contains(&([1, 0], 1)));
+    assert!(assignment.contains(&([0, 0], 0)) && assignment.contains(&([2, 0], 0)));
+  }
+}
+
+/// Pins every cell to a tile, without being able to sample
+#[derive(Clone)]
+struct Pin(usize);
+
+impl CellSampler<4, [usize; 2]> for Pin {
+  fn sample_cell(
+    &mut self,
+    _cell: &Cell<'_, 4, [usize; 2]>,
+    _entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    Err(SampleError::Empty)
+  }
+
+  fn pinned(&self, _cell: &Cell<'_, 4, [usize; 2]>) -> Option<usize> {
+    Some(self.0)
+  }
+}
+
+#[test]
+fn pinned_tiles_are_picked_without_sampling() {
+  let constraint = any(2);
+  let grid = Cartesian2([1, 1]);
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, Pin(1))
+    .with_value_order(LeastConstraining::default());
+
+  let actions = state.get_actions();
+  assert!(matches!(state.pick_action(actions.iter()), Ok(([0, 0], 1))));
+  assert_eq!(
+    state.pick_action(actions.iter().filter(|(_, tile)| *tile == 0)),
+    Err(PickActionError::Sample(SampleError::Unavailable(1)))
+  );
+}
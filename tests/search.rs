@@ -0,0 +1,283 @@
+mod common;
+
+use common::{any, colours};
+use std::{cmp::Reverse, collections::BTreeSet};
+use wfc::{
+  consistency::{CSPDomains, Constraint},
+  prelude::*,
+  traits::*,
+};
+
+type Map = WFCState<'static, 4, [usize; 2], Cartesian2, First>;
+
+/// Collects the distinct assignments of every solution found by a search
+fn solutions(
+  search: impl Iterator<Item = Result<Map, WFCError<[usize; 2]>>>,
+) -> BTreeSet<Vec<([usize; 2], usize)>> {
+  search
+    .filter_map(Result::ok)
+    .map(|state| state.domains().assignment().unwrap())
+    .collect()
+}
+
+/// Leaks a grid and constraint, so that states can be sent to other threads
+fn leak(
+  grid: Cartesian2,
+  constraint: Constraint<4>,
+) -> (&'static Cartesian2, &'static Constraint<4>) {
+  (Box::leak(Box::new(grid)), Box::leak(Box::new(constraint)))
+}
+
+#[test]
+fn par_backtrack_finds_the_same_solutions_as_backtrack() {
+  // a 3 colouring of a 2x3 grid has 3 * 2 * 3 * 3 = 54 solutions
+  let (grid, constraint) = leak(Cartesian2([2, 3]), colours(3));
+  let start = || WFCState::new(grid.cells(), grid, constraint, First);
+
+  let expected = solutions(Backtrack::new(start()));
+  assert_eq!(expected.len(), 54);
+  assert_eq!(solutions(ParBacktrack::new(start())), expected);
+  assert_eq!(
+    solutions(ParBacktrack::with_depth(start(), 4).with_bound(1)),
+    expected
+  );
+}
+
+/// Counts the cells assigned tile `1` so far
+fn ones(state: &WFCState<'_, 4, [usize; 2], Cartesian2, First>) -> usize {
+  (state.domains().keys().into_iter())
+    .filter(|idx| state.domains().read_at(idx, |d| d.single_item()) == Some(Some(1)))
+    .count()
+}
+
+#[test]
+fn best_first_finds_the_best_solution_first() {
+  let constraint = any(2);
+  let grid = Cartesian2([1, 3]);
+  let start = || WFCState::new(grid.cells(), &grid, &constraint, First);
+
+  let best = BestFirst::new(ScoreBy::new(start(), ones))
+    .next_valid()
+    .unwrap();
+  assert_eq!(ones(best.state()), 3);
+  let worst = BestFirst::new(ScoreBy::new(start(), |state: &_| Reverse(ones(state))))
+    .next_valid()
+    .unwrap();
+  assert_eq!(ones(worst.state()), 0);
+}
+
+#[test]
+fn beam_only_keeps_the_best_states_at_each_level() {
+  let constraint = any(2);
+  let grid = Cartesian2([1, 3]);
+  let start = || WFCState::new(grid.cells(), &grid, &constraint, First);
+  let beam = |width| Beam::with_width(ScoreBy::new(start(), ones), width);
+
+  // each level doubles the states, so the last level has twice the width
+  let best: Vec<_> = beam(1)
+    .filter_map(Result::ok)
+    .map(|s| ones(s.state()))
+    .collect();
+  assert_eq!(best, [3, 2]);
+  assert_eq!(beam(2).filter_map(Result::ok).count(), 4);
+  assert_eq!(beam(8).filter_map(Result::ok).count(), 8);
+}
+
+#[test]
+fn scored_states_forward_propagation_tasks() {
+  let constraint = colours(3);
+  let grid = Cartesian2([2, 2]);
+  let start = || WFCState::new(grid.cells(), &grid, &constraint, First);
+
+  let mut plain = Backtrack::new(start());
+  plain.by_ref().for_each(drop);
+  let mut scored = Backtrack::new(ScoreBy::new(start(), ones));
+  scored.by_ref().for_each(drop);
+  assert!(plain.stats().propagation_tasks > 0);
+  assert_eq!(
+    scored.stats().propagation_tasks,
+    plain.stats().propagation_tasks
+  );
+}
+
+/// Counts the cells collapsed to a single tile
+fn collapsed(domains: &CSPDomains<4, [usize; 2]>) -> usize {
+  (domains.keys().into_iter())
+    .filter(|idx| domains.read_at(idx, |d| d.is_single()).unwrap())
+    .count()
+}
+
+#[test]
+fn failed_searches_keep_their_best_partial_state() {
+  // a wrapped 3x3 grid has odd cycles, so it can't be 2 coloured
+  let constraint = colours(2);
+  let grid = Wrapped2([3, 3]);
+  let start = || WFCState::new(grid.cells(), &grid, &constraint, First);
+
+  let mut naive = Naive::new(start());
+  assert!(naive.next().is_some_and(|result| result.is_err()));
+  assert!(naive.best_partial().is_some_and(|best| !best.is_goal()));
+
+  let mut backtrack = Backtrack::new(start());
+  assert_eq!(backtrack.by_ref().filter(Result::is_ok).count(), 0);
+  assert!(backtrack.best_partial().is_some_and(|best| !best.is_goal()));
+}
+
+#[test]
+fn backtrack_keeps_the_deepest_dead_end() {
+  // fixing 2 cells of the odd cycles leaves some partial colourings that can't
+  // be completed, alongside 10 that can
+  let constraint = colours(3);
+  let grid = Wrapped2([3, 5]);
+  let sampler = Fixed::new([([0, 0], 0), ([1, 0], 1)], First);
+  let mut backtrack = Backtrack::new(WFCState::new(grid.cells(), &grid, &constraint, sampler));
+  assert_eq!(backtrack.by_ref().filter(Result::is_ok).count(), 10);
+
+  // states that led to a solution (i.e. the start state) aren't dead ends
+  let best = backtrack.best_partial().unwrap();
+  assert!(!best.is_goal());
+  assert!(collapsed(best.domains()) > 0);
+  assert_eq!(count_solutions(best.clone(), 1), SolutionCount::Exact(0));
+}
+
+#[test]
+fn successful_searches_have_no_best_partial_state() {
+  let constraint = any(2);
+  let grid = Cartesian2([3, 3]);
+  let mut naive = Naive::new(WFCState::new(grid.cells(), &grid, &constraint, First));
+  assert!(naive.next().is_some_and(|result| result.is_ok()));
+  assert!(naive.best_partial().is_none());
+
+  // every state on a path of 3 cells leads to one of the 2 colourings
+  let constraint = colours(2);
+  let grid = Cartesian2([1, 3]);
+  let mut backtrack = Backtrack::new(WFCState::new(grid.cells(), &grid, &constraint, First));
+  assert_eq!(backtrack.by_ref().filter(Result::is_ok).count(), 2);
+  assert!(backtrack.best_partial().is_none());
+}
+
+#[test]
+fn restarts_sample_differently_each_attempt() {
+  let constraint = colours(3);
+  let grid = Wrapped2([4, 4]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, Uniform::from_seed(5));
+  let attempts: BTreeSet<_> = (Restart::new(state).take(10))
+    .map(|result| result.map(|state| state.domains().assignment()).ok())
+    .collect();
+  assert!(attempts.len() > 1, "every attempt was the same");
+}
+
+#[test]
+fn stats_count_the_work_done() {
+  // colouring a path of 3 cells, any choice forces the other 2 cells
+  let constraint = colours(2);
+  let grid = Cartesian2([1, 3]);
+  let mut backtrack = Backtrack::new(WFCState::new(grid.cells(), &grid, &constraint, First));
+  assert_eq!(backtrack.by_ref().filter(Result::is_ok).count(), 2);
+
+  // each choice propagates along both edges of the path in both directions,
+  // which is 4 tasks
+  let stats = backtrack.stats();
+  assert_eq!(stats.decisions, 2);
+  assert_eq!(stats.backtracks, 1);
+  assert_eq!(stats.contradictions, 0);
+  assert_eq!(stats.propagation_tasks, 8);
+  assert_eq!(stats.peak_depth, 1);
+}
+
+#[test]
+fn solutions_are_counted_exactly_below_the_limit() {
+  let wrapped = |grid: Wrapped2, no_colours, limit| {
+    let constraint = colours(no_colours);
+    count_solutions(
+      WFCState::new(grid.cells(), &grid, &constraint, First),
+      limit,
+    )
+  };
+  let cartesian = |grid: Cartesian2, no_colours, limit| {
+    let constraint = colours(no_colours);
+    count_solutions(
+      WFCState::new(grid.cells(), &grid, &constraint, First),
+      limit,
+    )
+  };
+
+  // a path has 2 colourings, and a cycle of 4 cells has 18 3 colourings
+  assert_eq!(
+    cartesian(Cartesian2([1, 3]), 2, 10),
+    SolutionCount::Exact(2)
+  );
+  assert_eq!(wrapped(Wrapped2([3, 3]), 3, 100), SolutionCount::Exact(12));
+  assert_eq!(
+    cartesian(Cartesian2([2, 2]), 3, 100),
+    SolutionCount::Exact(18)
+  );
+  assert_eq!(wrapped(Wrapped2([3, 3]), 2, 10), SolutionCount::Exact(0));
+  assert_eq!(
+    cartesian(Cartesian2([2, 2]), 3, 5),
+    SolutionCount::AtLeast(5)
+  );
+}
+
+#[test]
+fn steps_describe_each_action() {
+  use WFCEvent::*;
+
+  // the last cell is fixed to the tile its neighbour is collapsed to first, so
+  // collapsing the neighbour contradicts and the next tile is tried instead
+  let constraint = colours(3);
+  let grid = Cartesian2([3, 1]);
+  let sampler = Fixed::new([([2, 0], 1)], First);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler).with_selector(Scanline);
+  let mut steps = Steps::new(state);
+  assert_eq!(
+    steps.by_ref().collect::<Vec<_>>(),
+    [
+      TilesBanned([0, 0], vec![1, 2]),
+      CellCollapsed([0, 0], 0),
+      TilesBanned([1, 0], vec![0]),
+      Contradiction,
+      TilesBanned([1, 0], vec![1]),
+      CellCollapsed([1, 0], 2),
+      TilesBanned([2, 0], vec![2]),
+      TilesBanned([2, 0], vec![0]),
+      CellCollapsed([2, 0], 1),
+    ]
+  );
+  let solution = steps.solution().unwrap();
+  assert_eq!(
+    solution.domains().assignment(),
+    Some(vec![([0, 0], 0), ([1, 0], 2), ([2, 0], 1)])
+  );
+
+  // only tile `0` is weighted, so the second cell fails to pick and backtracks
+  // to the first cell, which then fails to pick from the tiles left
+  let sampler = Weighted::from_seed(0, [1.0, 0.0, 0.0]).unwrap();
+  let state = WFCState::new(grid.cells(), &grid, &constraint, sampler).with_selector(Scanline);
+  let mut steps = Steps::new(state);
+  let failed = PickFailed(PickActionError::Sample(SampleError::ZeroWeight));
+  assert_eq!(
+    steps.by_ref().collect::<Vec<_>>(),
+    [
+      TilesBanned([0, 0], vec![1, 2]),
+      CellCollapsed([0, 0], 0),
+      TilesBanned([1, 0], vec![0]),
+      failed.clone(),
+      Backtracked(0),
+      failed,
+    ]
+  );
+  assert!(steps.solution().is_none());
+
+  // a wrapped 3x3 grid has odd cycles, so both tiles of the first cell
+  // contradict, leaving nothing to backtrack to
+  let constraint = colours(2);
+  let grid = Wrapped2([3, 3]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, First);
+  let mut steps = Steps::new(state);
+  assert_eq!(
+    steps.by_ref().collect::<Vec<_>>(),
+    [Contradiction, Contradiction]
+  );
+  assert!(steps.solution().is_none());
+}
@@ -0,0 +1,225 @@
+mod common;
+
+use common::{any, colours, Side};
+use std::sync::{Arc, Mutex};
+use wfc::{consistency::Constraint, prelude::*, selection::Cell, traits::*};
+
+/// The cell a state would collapse next
+fn selected<S: State<Action = ([usize; 2], usize)>>(state: &S) -> Option<[usize; 2]> {
+  state.get_actions().into_iter().next().map(|(idx, _)| idx)
+}
+
+/// Collapses the given cells in turn, panicking if any of them fail
+fn collapse<S: State<Action = ([usize; 2], usize)>>(
+  state: S,
+  actions: &[([usize; 2], usize)],
+) -> S {
+  actions
+    .iter()
+    .fold(state, |state, action| match state.take_action(action) {
+      Ok(state) => state,
+      Err(_) => panic!("{action:?} can be taken"),
+    })
+}
+
+#[test]
+fn mrv_selects_the_cell_with_the_fewest_tiles() {
+  let constraint = colours(3);
+  let grid = Cartesian2([3, 3]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, First).with_selector(Mrv);
+
+  let state = collapse(state, &[([0, 0], 0)]);
+  assert!(matches!(selected(&state), Some([1, 0] | [0, 1])));
+}
+
+#[test]
+fn mrv_degree_breaks_ties_by_the_neighbours() {
+  let constraint = colours(3);
+  let grid = Cartesian2([3, 3]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, First);
+
+  // every cell has 3 tiles left, but the centre has the most neighbours
+  assert_eq!(selected(&state), Some([1, 1]));
+  let state = collapse(state, &[([1, 1], 0)]);
+  assert!(matches!(
+    selected(&state),
+    Some([0, 1] | [1, 0] | [2, 1] | [1, 2])
+  ));
+}
+
+#[test]
+fn entropy_selects_the_cell_with_the_lowest_entropy() {
+  let constraint = colours(3);
+  let grid = Cartesian2([3, 3]);
+  let entropy = Entropy::new(vec![1.0, 1.0, 8.0]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, First).with_selector(entropy);
+
+  // both corners leave their neighbours with 2 tiles, but the tiles next to
+  // `[2, 2]` have far less even weights
+  let state = collapse(state, &[([0, 0], 2), ([2, 2], 0)]);
+  assert!(matches!(selected(&state), Some([1, 2] | [2, 1])));
+}
+
+#[test]
+fn random_orders_depend_on_the_seed() {
+  let constraint = any(2);
+  let grid = Cartesian2([8, 8]);
+  let first = |seed| {
+    let state = WFCState::new(grid.cells(), &grid, &constraint, First);
+    selected(&state.with_selector(RandomOrder::new(seed)))
+  };
+
+  assert_eq!(first(0), first(0));
+  assert!((1..8).any(|seed| first(seed) != first(0)));
+}
+
+#[test]
+fn scanline_selects_cells_in_order() {
+  let constraint = any(2);
+  let grid = Cartesian2([3, 3]);
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, First).with_selector(Scanline);
+
+  for idx in grid.cells() {
+    assert_eq!(selected(&state), Some(idx));
+    state = collapse(state, &[(idx, 0)]);
+  }
+  assert_eq!(selected(&state), None);
+}
+
+#[test]
+fn spiral_selects_cells_outwards_from_its_seed() {
+  let constraint = any(2);
+  let grid = Cartesian2([3, 3]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, First);
+  let state = state.with_selector(Spiral::new([1, 1]));
+
+  assert_eq!(selected(&state), Some([1, 1]));
+  let state = collapse(state, &[([1, 1], 0)]);
+  assert_eq!(selected(&state), Some([2, 1]));
+  let state = collapse(state, &[([2, 1], 0)]);
+  assert_eq!(selected(&state), Some([2, 2]));
+}
+
+#[test]
+fn dom_wdeg_selects_cells_with_the_most_weighted_constraints() {
+  let constraint = colours(3);
+  let grid = Cartesian2([3, 3]);
+  let start =
+    |selector| WFCState::new(grid.cells(), &grid, &constraint, First).with_selector(selector);
+
+  // with every weight at 1, the centre has the most neighbours
+  let selector = DomWdeg::new();
+  assert_eq!(selected(&start(selector.clone())), Some([1, 1]));
+
+  for _ in 0..3 {
+    CellSelector::<4, _>::wiped_out(&selector, [(&[0, 0], 2), (&[1, 0], 0)]);
+  }
+  assert_eq!(selector.weight([1, 0], 0), 4);
+  assert_eq!(selected(&start(selector)), Some([1, 0]));
+}
+
+#[test]
+fn dom_wdeg_learns_from_failed_restarts() {
+  // @note each row is an odd cycle, so it can't be 2 coloured
+  let constraint = colours(2);
+  let grid = Wrapped2([3, 4]);
+  let selector = DomWdeg::new();
+  let state =
+    WFCState::new(grid.cells(), &grid, &constraint, First).with_selector(selector.clone());
+  let learnt = |idx| (0..4).any(|side| selector.weight(idx, side) > 1);
+  let first = selected(&state).unwrap();
+
+  let mut restart = Restart::new(state.clone());
+  assert!(restart.next().unwrap().is_err());
+  assert_eq!(selector.wipeouts(), 1);
+
+  // the restarted state shares the weights, so it starts next to the constraint
+  // that wiped out a domain (which may be anywhere, as propagation is parallel)
+  let next = selected(&state).unwrap();
+  assert!(learnt(next));
+  assert!(learnt(first) || next != first);
+}
+
+#[test]
+fn every_cell_is_reranked_once_the_selector_changes() {
+  let constraint = colours(3);
+  let grid = Cartesian2([3, 3]);
+  let selector = DomWdeg::new();
+  let state =
+    WFCState::new(grid.cells(), &grid, &constraint, First).with_selector(selector.clone());
+  assert_eq!(selected(&state), Some([1, 1]));
+
+  // the weights change without any domains changing, so the state's queue is
+  // stale and every cell has to be ranked again
+  for _ in 0..3 {
+    CellSelector::<4, _>::wiped_out(&selector, [(&[0, 0], 2), (&[1, 0], 0)]);
+  }
+  assert_eq!(selected(&state), Some([1, 0]));
+
+  // collapsing `[2, 2]` doesn't change `[1, 0]` or its neighbours, so it's only
+  // re-ranked in the new state because the selector's version changed
+  let state = collapse(state, &[([2, 2], 0)]);
+  assert_eq!(selected(&state), Some([1, 0]));
+}
+
+#[test]
+fn cells_are_reranked_as_their_neighbours_change() {
+  let constraint = colours(3);
+  let grid = Cartesian2([1, 5]);
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, First);
+
+  // each collapse leaves the next cell along with 2 tiles, and the rest with 3
+  for y in 0..5 {
+    assert!(y == 0 || selected(&state) == Some([0, y]));
+    state = collapse(state, &[([0, y], y % 2)]);
+  }
+  assert_eq!(selected(&state), None);
+}
+
+/// Tiles `0` and `3` fit next to anything, tile `1` fits next to anything but
+/// tile `2`, and tile `2` only fits next to tiles `0` and `3`
+struct Fussy(usize);
+
+impl Tileable<Side> for Fussy {
+  fn tiles(&self, other: &Self, _: &Side) -> bool {
+    matches!((self.0, other.0), (0 | 3, _) | (_, 0 | 3) | (1, 1))
+  }
+}
+
+/// Records the tiles it's given to sample from, taking the first of them
+#[derive(Clone, Default)]
+struct Record(Arc<Mutex<Vec<usize>>>);
+
+impl CellSampler<4, [usize; 2]> for Record {
+  fn sample_cell(
+    &mut self,
+    _cell: &Cell<'_, 4, [usize; 2]>,
+    entries: &[usize],
+  ) -> Result<usize, SampleError> {
+    *self.0.lock().unwrap() = entries.to_vec();
+    First.sample(entries)
+  }
+}
+
+#[test]
+fn least_constraining_values_are_sampled_from() {
+  // collapsing the first cell to each tile leaves its neighbour with 4, 3, 2
+  // and 4 tiles, removing 9, 10, 11 and 9 of the 13 supports between them
+  let constraint = Constraint::new(&[Fussy(0), Fussy(1), Fussy(2), Fussy(3)], &[Side; 4]);
+  let grid = Cartesian2([2, 1]);
+  let sampled = |order| {
+    let record = Record::default();
+    let mut state = WFCState::new(grid.cells(), &grid, &constraint, record.clone())
+      .with_selector(Scanline)
+      .with_value_order(LeastConstraining::new(order));
+    let actions = state.get_actions();
+    assert!(matches!(state.pick_action(actions.iter()), Ok(([0, 0], 0))));
+    let entries = record.0.lock().unwrap().clone();
+    entries
+  };
+
+  // only the tiles removing the fewest supports are left as ties
+  assert_eq!(sampled(Combine::Tiebreak), [0, 3]);
+  // each tile is repeated once for every tile that removes more supports
+  assert_eq!(sampled(Combine::Weighting), [0, 0, 0, 1, 1, 2, 3, 3, 3]);
+}
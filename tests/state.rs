@@ -0,0 +1,198 @@
+mod common;
+
+use common::{any, colours};
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+};
+use wfc::{
+  consistency::{AC3Error, CSPDomains},
+  prelude::*,
+  traits::*,
+};
+
+#[test]
+fn reset_regions_keep_the_cells_around_them() {
+  let constraint = colours(3);
+  let grid = Cartesian2([4, 4]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, Uniform::from_seed(0));
+  let solved = Backtrack::new(state).find_map(Result::ok).unwrap();
+  let before: HashMap<_, _> = solved.domains().assignment().unwrap().into_iter().collect();
+
+  let region = grid.cells_between([1, 1], [3, 3]);
+  let Ok(reset) = solved.reset_region(region.clone()) else {
+    panic!("the solved state can be reset");
+  };
+  for idx in grid.cells() {
+    let domain = reset.domains().read_at(&idx, Clone::clone).unwrap();
+    assert!(domain.contains(before[&idx]));
+    if !region.contains(&idx) {
+      assert!(domain.is_single());
+    }
+  }
+
+  // only the region is regenerated
+  let regenerated = Backtrack::new(reset).find_map(Result::ok).unwrap();
+  for (idx, tile) in regenerated.domains().assignment().unwrap() {
+    if !region.contains(&idx) {
+      assert_eq!(tile, before[&idx]);
+    }
+  }
+}
+
+#[test]
+fn reset_regions_are_only_constrained_by_their_surroundings() {
+  let constraint = any(3);
+  let grid = Cartesian2([4, 4]);
+  let state = WFCState::new(grid.cells(), &grid, &constraint, First);
+  let solved = Backtrack::new(state).find_map(Result::ok).unwrap();
+
+  let region = grid.cells_between([1, 1], [3, 3]);
+  let Ok(reset) = solved.reset_region(region.clone()) else {
+    panic!("the solved state can be reset");
+  };
+  for idx in grid.cells() {
+    let size = reset.domains().read_at(&idx, |d| d.iter().count()).unwrap();
+    assert_eq!(size, if region.contains(&idx) { 3 } else { 1 });
+  }
+}
+
+/// Vetoes every tile in a set, counting each veto
+#[derive(Clone, Default)]
+struct Veto {
+  tiles: Vec<usize>,
+  vetoed: Arc<AtomicUsize>,
+}
+
+impl WfcObserver<4, [usize; 2]> for Veto {
+  fn on_select(&self, _idx: &[usize; 2], tile: usize) -> bool {
+    let allowed = !self.tiles.contains(&tile);
+    if !allowed {
+      self.vetoed.fetch_add(1, Ordering::Relaxed);
+    }
+    allowed
+  }
+}
+
+#[test]
+fn observers_can_veto_tiles() {
+  let constraint = any(2);
+  let grid = Cartesian2([3, 3]);
+  let veto = Veto {
+    tiles: vec![0],
+    ..Default::default()
+  };
+  let state = WFCState::new(grid.cells(), &grid, &constraint, First).with_observer(veto.clone());
+
+  let solved = Naive::new(state).find_map(Result::ok).unwrap();
+  let assignment = solved.domains().assignment().unwrap();
+  assert!(assignment.iter().all(|(_, tile)| *tile == 1));
+  // `First` picks tile 0 for each of the 9 cells before it's vetoed
+  assert_eq!(veto.vetoed.load(Ordering::Relaxed), 9);
+}
+
+#[test]
+fn vetoing_every_tile_fails_to_pick() {
+  let constraint = any(2);
+  let grid = Cartesian2([3, 3]);
+  let veto = Veto {
+    tiles: vec![0, 1],
+    ..Default::default()
+  };
+  let mut state = WFCState::new(grid.cells(), &grid, &constraint, First).with_observer(veto);
+
+  let actions = state.get_actions();
+  assert_eq!(
+    state.pick_action(actions.iter()),
+    Err(PickActionError::Vetoed)
+  );
+}
+
+#[test]
+fn searches_taking_every_action_respect_vetoes() {
+  let grid: &'static _ = Box::leak(Box::new(Cartesian2([2, 2])));
+  let constraint: &'static _ = Box::leak(Box::new(any(2)));
+  let veto = Veto {
+    tiles: vec![0],
+    ..Default::default()
+  };
+  let start = || WFCState::new(grid.cells(), grid, constraint, First).with_observer(veto.clone());
+  let all_ones = |state: &WFCState<_, _, _, _, _, Veto>| {
+    let assignment = state.domains().assignment().unwrap();
+    assignment.iter().all(|(_, tile)| *tile == 1)
+  };
+
+  let solutions: Vec<_> = BestFirst::new(ScoreBy::new(start(), |_: &_| 0))
+    .filter_map(Result::ok)
+    .collect();
+  assert_eq!(solutions.len(), 1);
+  assert!(all_ones(solutions[0].state()));
+
+  let solutions: Vec<_> = Beam::new(ScoreBy::new(start(), |_: &_| 0))
+    .filter_map(Result::ok)
+    .collect();
+  assert_eq!(solutions.len(), 1);
+  assert!(all_ones(solutions[0].state()));
+
+  let solutions: Vec<_> = ParBacktrack::with_depth(start(), 2)
+    .filter_map(Result::ok)
+    .collect();
+  assert_eq!(solutions.len(), 1);
+  assert!(all_ones(&solutions[0]));
+}
+
+/// Counts every propagation, contradiction and backtrack
+#[derive(Clone, Default)]
+struct Recorder {
+  propagations: Arc<AtomicUsize>,
+  contradictions: Arc<AtomicUsize>,
+  backtracks: Arc<AtomicUsize>,
+}
+
+impl WfcObserver<4, [usize; 2]> for Recorder {
+  fn on_propagate(&self, _idx: &[usize; 2], _tile: usize, _domains: &CSPDomains<4, [usize; 2]>) {
+    self.propagations.fetch_add(1, Ordering::Relaxed);
+  }
+
+  fn on_contradiction(&self, _error: &AC3Error<[usize; 2]>) {
+    self.contradictions.fetch_add(1, Ordering::Relaxed);
+  }
+
+  fn on_backtrack(&self, _depth: usize) {
+    self.backtracks.fetch_add(1, Ordering::Relaxed);
+  }
+}
+
+#[test]
+fn observers_are_called_as_states_are_generated() {
+  let grid = Wrapped2([3, 3]);
+  let (two, three) = (colours(2), colours(3));
+  let count = |counter: &AtomicUsize| counter.load(Ordering::Relaxed);
+  let search = |constraint| {
+    let recorder = Recorder::default();
+    let state = WFCState::new(grid.cells(), &grid, constraint, First);
+    let mut search = Backtrack::new(state.with_observer(recorder.clone()));
+    let solutions = search.by_ref().filter(Result::is_ok).count();
+    (solutions, search.stats(), recorder)
+  };
+
+  // a wrapped 3x3 grid has odd cycles, so every 2 colouring contradicts
+  let (solutions, stats, recorder) = search(&two);
+  assert_eq!(solutions, 0);
+  assert!(stats.contradictions > 0);
+  assert_eq!(count(&recorder.contradictions), stats.contradictions);
+  assert_eq!(
+    count(&recorder.propagations),
+    stats.decisions - stats.contradictions
+  );
+
+  // every dead end but the start state is backtracked out of
+  let (solutions, stats, recorder) = search(&three);
+  assert_eq!(solutions, 12);
+  assert_eq!(count(&recorder.propagations), stats.decisions);
+  assert!(count(&recorder.backtracks) > 0);
+  assert_eq!(count(&recorder.backtracks), stats.backtracks - 1);
+}